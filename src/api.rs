@@ -0,0 +1,412 @@
+use std::time::Duration;
+
+use reqwest::{
+    Method, StatusCode,
+    header::{self, HeaderMap},
+};
+use serde::Serialize;
+
+use crate::endpoint::Endpoint;
+use crate::models::{
+    CancelShippingRequest, ConfirmEstimationShippingRequest, ContentType, CoverageRequest, Error,
+    EstimationShippingRequest, HttpErrorResponse, ResponseContent, WayPointModel,
+    WebhooksConfigModel,
+};
+use crate::rate_limit::{RateLimitMode, RateLimiter};
+use crate::retry::RetryPolicy;
+
+/// Settings shared by [`crate::PedidosYaClient`] and [`crate::PedidosYaBlockingClient`].
+#[derive(Debug, Clone)]
+pub(crate) struct ClientConfig {
+    pub(crate) base_path: String,
+    pub(crate) auth_header: header::HeaderValue,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+}
+
+impl ClientConfig {
+    pub(crate) fn url(&self, request: &ApiRequest) -> String {
+        format!("{}{}", self.base_path, request.path)
+    }
+
+    /// Takes a rate limit permit for `endpoint`. Returns how long to wait before trying
+    /// again when none is available, or [`Error::RateLimited`] when failing fast.
+    pub(crate) fn try_permit<E>(&self, endpoint: Endpoint) -> Result<Option<Duration>, Error<E>> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(None);
+        };
+        match rate_limiter.try_acquire(endpoint) {
+            Ok(()) => Ok(None),
+            Err(retry_after) if rate_limiter.mode() == RateLimitMode::FailFast => {
+                Err(Error::RateLimited {
+                    endpoint,
+                    retry_after,
+                })
+            }
+            Err(wait) => Ok(Some(wait)),
+        }
+    }
+}
+
+/// Transport independent description of a call to the courier API.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiRequest {
+    pub(crate) endpoint: Endpoint,
+    pub(crate) method: Method,
+    pub(crate) path: String,
+    pub(crate) body: Option<Vec<u8>>,
+    /// Identifiers of the order the call is about, only used for tracing.
+    shipping_id: Option<String>,
+    estimate_id: Option<String>,
+    reference_id: Option<String>,
+}
+
+impl ApiRequest {
+    fn new(endpoint: Endpoint, method: Method, path: impl Into<String>) -> Self {
+        Self {
+            endpoint,
+            method,
+            path: path.into(),
+            body: None,
+            shipping_id: None,
+            estimate_id: None,
+            reference_id: None,
+        }
+    }
+
+    fn json<T: Serialize>(mut self, body: &T) -> Result<Self, serde_json::Error> {
+        self.body = Some(serde_json::to_vec(body)?);
+        Ok(self)
+    }
+
+    fn shipping_id(mut self, shipping_id: &str) -> Self {
+        self.shipping_id = Some(shipping_id.to_owned());
+        self
+    }
+
+    fn estimate_id(mut self, estimate_id: &str) -> Self {
+        self.estimate_id = Some(estimate_id.to_owned());
+        self
+    }
+
+    fn reference_id(mut self, reference_id: &str) -> Self {
+        self.reference_id = Some(reference_id.to_owned());
+        self
+    }
+
+    /// Span covering the whole call, retries included. `status`, `attempt` and `latency_ms`
+    /// are recorded by the client once the final response is received.
+    ///
+    /// The request body and headers are deliberately left out: they carry the auth token
+    /// and, for the webhooks configuration, the callback authorization keys.
+    pub(crate) fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "pedidosya_request",
+            endpoint = %self.endpoint,
+            method = %self.method,
+            shipping_id = self.shipping_id.as_deref(),
+            estimate_id = self.estimate_id.as_deref(),
+            reference_id = self.reference_id.as_deref(),
+            status = tracing::field::Empty,
+            attempt = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        )
+    }
+
+    pub(crate) fn check_coverage(
+        pickup: WayPointModel,
+        dropoff: WayPointModel,
+    ) -> Result<Self, serde_json::Error> {
+        Self::new(
+            Endpoint::CheckCoverage,
+            Method::POST,
+            "/v3/estimates/coverage",
+        )
+        .json(&CoverageRequest::new(pickup, dropoff))
+    }
+
+    pub(crate) fn estimate_shipping(
+        estimation_shipping_request: &EstimationShippingRequest,
+    ) -> Result<Self, serde_json::Error> {
+        Self::new(
+            Endpoint::EstimateShipping,
+            Method::POST,
+            "/v3/shippings/estimates",
+        )
+        .reference_id(&estimation_shipping_request.reference_id)
+        .json(estimation_shipping_request)
+    }
+
+    pub(crate) fn confirm_estimate(
+        estimate_id: &str,
+        confirm_estimate_request: &ConfirmEstimationShippingRequest,
+    ) -> Result<Self, serde_json::Error> {
+        Self::new(
+            Endpoint::ConfirmEstimate,
+            Method::POST,
+            format!("/v3/shippings/estimates/{estimate_id}/confirm"),
+        )
+        .estimate_id(estimate_id)
+        .json(confirm_estimate_request)
+    }
+
+    pub(crate) fn create_shipping(
+        shipping_request: &EstimationShippingRequest,
+    ) -> Result<Self, serde_json::Error> {
+        Self::new(Endpoint::CreateShipping, Method::POST, "/v3/shippings")
+            .reference_id(&shipping_request.reference_id)
+            .json(shipping_request)
+    }
+
+    pub(crate) fn get_shipping(shipping_id: &str) -> Self {
+        Self::new(
+            Endpoint::GetShipping,
+            Method::GET,
+            format!("/v3/shippings/{shipping_id}"),
+        )
+        .shipping_id(shipping_id)
+    }
+
+    pub(crate) fn cancel_shipping(
+        shipping_id: &str,
+        reason: String,
+    ) -> Result<Self, serde_json::Error> {
+        Self::new(
+            Endpoint::CancelShipping,
+            Method::PUT,
+            format!("/v3/shippings/{shipping_id}/cancel"),
+        )
+        .shipping_id(shipping_id)
+        .json(&CancelShippingRequest::new(reason))
+    }
+
+    pub(crate) fn get_shipping_tracking(shipping_id: &str) -> Self {
+        Self::new(
+            Endpoint::GetShippingTracking,
+            Method::GET,
+            format!("/v3/shippings/{shipping_id}/tracking"),
+        )
+        .shipping_id(shipping_id)
+    }
+
+    pub(crate) fn get_proof_of_delivery(shipping_id: &str) -> Self {
+        Self::new(
+            Endpoint::GetProofOfDelivery,
+            Method::GET,
+            format!("/v3/shippings/{shipping_id}/proofOfDelivery"),
+        )
+        .shipping_id(shipping_id)
+    }
+
+    pub(crate) fn get_webhooks_configuration() -> Self {
+        Self::new(
+            Endpoint::GetWebhooksConfiguration,
+            Method::GET,
+            "/v3/webhooks-configuration",
+        )
+    }
+
+    pub(crate) fn set_webhooks_configuration(
+        webhook_config_request: &WebhooksConfigModel,
+    ) -> Result<Self, serde_json::Error> {
+        Self::new(
+            Endpoint::SetWebhooksConfiguration,
+            Method::PUT,
+            "/v3/webhooks-configuration",
+        )
+        .json(webhook_config_request)
+    }
+}
+
+pub(crate) fn content_type(headers: &HeaderMap) -> ContentType {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .into()
+}
+
+/// Decodes a JSON response into `Res`, or into the typed error `E` for non-success statuses.
+pub(crate) fn decode_json<Res, E>(
+    status: StatusCode,
+    content_type: ContentType,
+    body: &[u8],
+) -> Result<Res, Error<E>>
+where
+    Res: serde::de::DeserializeOwned,
+    E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
+{
+    if !status.is_success() {
+        return Err(error_response(status, content_type, body));
+    }
+    if body.is_empty() {
+        return Err(Error::EmptyResponse { status });
+    }
+    if !matches!(content_type, ContentType::Json) {
+        return Err(unexpected_content_type(status, content_type, body));
+    }
+
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|e| (e.path().to_string(), e.into_inner()))
+        .and_then(|value| {
+            deserializer
+                .end()
+                .map(|()| value)
+                .map_err(|e| (".".to_owned(), e))
+        })
+        .map_err(|(path, source)| Error::Decode {
+            status,
+            path,
+            body: String::from_utf8_lossy(body).into_owned(),
+            source,
+        })
+}
+
+/// Error for a response to an endpoint answering with a PDF document, given that it either
+/// failed or is not a PDF. Successful PDF responses are handed over without reading the body.
+pub(crate) fn pdf_error<E>(status: StatusCode, content_type: ContentType, body: &[u8]) -> Error<E>
+where
+    E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
+{
+    if !status.is_success() {
+        error_response(status, content_type, body)
+    } else if body.is_empty() {
+        Error::EmptyResponse { status }
+    } else {
+        unexpected_content_type(status, content_type, body)
+    }
+}
+
+pub(crate) fn error_response<E>(
+    status: StatusCode,
+    content_type: ContentType,
+    body: &[u8],
+) -> Error<E>
+where
+    E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
+{
+    match content_type {
+        ContentType::Json => {
+            let content = String::from_utf8_lossy(body).into_owned();
+            let entity: Option<E> = serde_json::from_str::<HttpErrorResponse>(&content)
+                .ok()
+                .map(E::from);
+
+            Error::ResponseError(ResponseContent {
+                status,
+                content,
+                entity,
+            })
+        }
+        // Error statuses without a body still carry their meaning in the status code.
+        _ if body.is_empty() => Error::ResponseError(ResponseContent {
+            status,
+            content: String::new(),
+            entity: None,
+        }),
+        content_type => unexpected_content_type(status, content_type, body),
+    }
+}
+
+fn unexpected_content_type<E>(
+    status: StatusCode,
+    content_type: ContentType,
+    body: &[u8],
+) -> Error<E> {
+    Error::UnexpectedContentType {
+        status,
+        content_type: content_type.into_header(),
+        body: String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::ShippingOderDetailsError;
+    use crate::models::ShippingResponse;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_typed_error() {
+        let body =
+            br#"{"status": 404, "message": "Shipping not found", "code": "SHIPPING_NOT_FOUND"}"#;
+
+        let result = decode_json::<ShippingResponse, ShippingOderDetailsError>(
+            StatusCode::NOT_FOUND,
+            ContentType::Json,
+            body,
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::ResponseError(ResponseContent {
+                entity: Some(ShippingOderDetailsError::Status404(_)),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_decode_unexpected_responses() {
+        let result = decode_json::<ShippingResponse, ShippingOderDetailsError>(
+            StatusCode::BAD_GATEWAY,
+            ContentType::Unsoported("text/html".to_owned()),
+            b"<html>502 Bad Gateway</html>",
+        );
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedContentType {
+                status: StatusCode::BAD_GATEWAY,
+                content_type: Some(ref content_type),
+                ref body,
+            }) if content_type == "text/html" && body.contains("Bad Gateway")
+        ));
+
+        let result = decode_json::<ShippingResponse, ShippingOderDetailsError>(
+            StatusCode::OK,
+            ContentType::None,
+            b"",
+        );
+        assert!(matches!(
+            result,
+            Err(Error::EmptyResponse {
+                status: StatusCode::OK
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decode_failure_keeps_path_and_body() {
+        let body = br#"{"shippingId": "64000", "status": "CONFIRMED", "cancelCode": 12}"#;
+
+        let result = decode_json::<ShippingResponse, ShippingOderDetailsError>(
+            StatusCode::OK,
+            ContentType::Json,
+            body,
+        );
+
+        match result {
+            Err(Error::Decode { path, body, .. }) => {
+                assert_eq!(path, "cancelCode");
+                assert!(body.contains("64000"));
+            }
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_request_paths() {
+        assert_eq!(
+            ApiRequest::get_shipping("64000").path,
+            "/v3/shippings/64000"
+        );
+        let request = ApiRequest::cancel_shipping("64000", "duplicated".to_owned()).unwrap();
+        assert_eq!(request.method, Method::PUT);
+        assert_eq!(request.path, "/v3/shippings/64000/cancel");
+        assert_eq!(
+            request.body.as_deref(),
+            Some(br#"{"reasonText":"duplicated"}"#.as_slice())
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use std::{io::Write, time::Instant};
+
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue};
+
+use crate::api::{self, ApiRequest, ClientConfig};
+use crate::client_builder::{ConfigError, PedidosYaClientBuilder};
+use crate::errors::{
+    CancelShippingError, ConfirmEstimateError, CoverageCheckError, CreateShippingError,
+    GetShippingsEstimatesError, ProofOfDeliveryError, ShippingOderDetailsError,
+    ShippingTrackingError, WebhookGetConfigurationError, WebhookSetConfigurationError,
+};
+use crate::models::{
+    ConfirmEstimationShippingRequest, ConfirmShippingResponse, ContentType, CoverageResponse,
+    Error, EstimationShippingResponse, HttpErrorResponse, ShippingResponse,
+    ShippingTrackingResponse, WayPointModel, WebhooksConfigModel,
+    estimation_shipping_request::EstimationShippingRequest,
+};
+
+/// Blocking counterpart of [`crate::PedidosYaClient`], with the same methods and error types.
+#[derive(Debug, Clone)]
+pub struct PedidosYaBlockingClient {
+    client: reqwest::blocking::Client,
+    config: ClientConfig,
+}
+
+impl PedidosYaBlockingClient {
+    /// Creates a client against the production API.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client cannot be built, see [`PedidosYaBlockingClient::try_new`].
+    pub fn new<S: Into<String>>(auth_token: S) -> Self {
+        Self::try_new(auth_token)
+            .expect("Error: could not initialize PedidosYa API client. Please try again!")
+    }
+
+    pub fn try_new<S: Into<String>>(auth_token: S) -> Result<Self, ConfigError> {
+        PedidosYaClientBuilder::new(auth_token).build_blocking()
+    }
+
+    pub fn builder<S: Into<String>>(auth_token: S) -> PedidosYaClientBuilder {
+        PedidosYaClientBuilder::new(auth_token)
+    }
+
+    pub(crate) fn from_parts(client: reqwest::blocking::Client, config: ClientConfig) -> Self {
+        Self { client, config }
+    }
+
+    fn build_request(
+        &self,
+        request: &ApiRequest,
+    ) -> Result<reqwest::blocking::Request, reqwest::Error> {
+        let mut builder = self
+            .client
+            .request(request.method.clone(), self.config.url(request))
+            .header(AUTHORIZATION, self.config.auth_header.clone());
+        if let Some(body) = &request.body {
+            builder = builder
+                .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+                .body(body.clone());
+        }
+        builder.build()
+    }
+
+    /// Executes `request`, retrying it as allowed by the client's [`crate::RetryPolicy`].
+    /// Every attempt takes its own rate limit permit.
+    ///
+    /// Runs inside the span of [`ApiRequest::span`], recording the final status, attempt
+    /// number and latency on it.
+    fn execute<E>(&self, request: &ApiRequest) -> Result<reqwest::blocking::Response, Error<E>> {
+        let span = tracing::Span::current();
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            while let Some(wait) = self.config.try_permit(request.endpoint)? {
+                tracing::debug!(?wait, "waiting for a rate limit permit");
+                std::thread::sleep(wait);
+            }
+
+            let attempt_started = Instant::now();
+            let result = self.client.execute(self.build_request(request)?);
+            let latency_ms = attempt_started.elapsed().as_millis() as u64;
+
+            let outcome = match &result {
+                Ok(response) => {
+                    tracing::debug!(
+                        attempt,
+                        status = response.status().as_u16(),
+                        latency_ms,
+                        "response received"
+                    );
+                    Ok((response.status(), response.headers()))
+                }
+                Err(e) => {
+                    tracing::debug!(attempt, latency_ms, error = %e, "request failed");
+                    Err(e as &(dyn std::error::Error + 'static))
+                }
+            };
+            match self
+                .config
+                .retry_policy
+                .retry_delay(request.endpoint, attempt, outcome)
+            {
+                Some(delay) => {
+                    tracing::debug!(attempt, ?delay, "retrying request");
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => {
+                    span.record("attempt", attempt);
+                    span.record("latency_ms", started.elapsed().as_millis() as u64);
+                    if let Ok(response) = &result {
+                        span.record("status", response.status().as_u16());
+                    }
+                    return Ok(result?);
+                }
+            }
+        }
+    }
+
+    fn send_post_request<Res, E>(&self, request: ApiRequest) -> Result<Res, Error<E>>
+    where
+        Res: serde::de::DeserializeOwned,
+        E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
+    {
+        let _span = request.span().entered();
+        let response = self.execute(&request)?;
+
+        let status = response.status();
+        let content_type = api::content_type(response.headers());
+        let body = response.bytes()?;
+
+        api::decode_json(status, content_type, &body)
+    }
+
+    fn send_pdf_request<E>(
+        &self,
+        request: ApiRequest,
+    ) -> Result<reqwest::blocking::Response, Error<E>>
+    where
+        E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
+    {
+        let _span = request.span().entered();
+        let response = self.execute(&request)?;
+
+        let status = response.status();
+        let content_type = api::content_type(response.headers());
+
+        if status.is_success() && matches!(content_type, ContentType::Pdf) {
+            return Ok(response);
+        }
+        let body = response.bytes()?;
+        Err(api::pdf_error(status, content_type, &body))
+    }
+
+    /// Checks whether the route between `pickup` and `dropoff` is served by the PedidosYa fleet,
+    /// so uncovered addresses can be rejected before requesting an estimate.
+    pub fn check_coverage(
+        &self,
+        pickup: WayPointModel,
+        dropoff: WayPointModel,
+    ) -> Result<CoverageResponse, Error<CoverageCheckError>> {
+        let request = ApiRequest::check_coverage(pickup, dropoff)?;
+
+        self.send_post_request(request)
+    }
+
+    pub fn shipping_estimate_shipping_order(
+        &self,
+        estimation_shipping_request: EstimationShippingRequest,
+    ) -> Result<EstimationShippingResponse, Error<GetShippingsEstimatesError>> {
+        let request = ApiRequest::estimate_shipping(&estimation_shipping_request)?;
+
+        self.send_post_request(request)
+    }
+
+    pub fn shipping_confirm_estimate_order(
+        &self,
+        estimate_id: impl Into<String>,
+        confirm_estimate_request: ConfirmEstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<ConfirmEstimateError>> {
+        let request = ApiRequest::confirm_estimate(&estimate_id.into(), &confirm_estimate_request)?;
+
+        self.send_post_request(request)
+    }
+
+    /// Creates and confirms a shipping order in a single call, without going through an estimate.
+    pub fn create_shipping(
+        &self,
+        shipping_request: EstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<CreateShippingError>> {
+        let request = ApiRequest::create_shipping(&shipping_request)?;
+
+        self.send_post_request(request)
+    }
+
+    pub fn shippings_shipping_oder_details_get(
+        &self,
+        shipping_id: impl Into<String>,
+    ) -> Result<ShippingResponse, Error<ShippingOderDetailsError>> {
+        let request = ApiRequest::get_shipping(&shipping_id.into());
+
+        self.send_post_request(request)
+    }
+
+    /// Cancels a shipping order. The returned order carries the resulting `cancel_code`.
+    pub fn cancel_shipping(
+        &self,
+        shipping_id: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Result<ShippingResponse, Error<CancelShippingError>> {
+        let request = ApiRequest::cancel_shipping(&shipping_id.into(), reason.into())?;
+
+        self.send_post_request(request)
+    }
+
+    /// Live tracking of a shipping order: rider position, vehicle and contact details.
+    pub fn get_shipping_tracking(
+        &self,
+        shipping_id: impl Into<String>,
+    ) -> Result<ShippingTrackingResponse, Error<ShippingTrackingError>> {
+        let request = ApiRequest::get_shipping_tracking(&shipping_id.into());
+
+        self.send_post_request(request)
+    }
+
+    /// Downloads the proof of delivery (signature or photo) of a shipping order as a PDF document.
+    pub fn get_proof_of_delivery(
+        &self,
+        shipping_id: impl Into<String>,
+    ) -> Result<Vec<u8>, Error<ProofOfDeliveryError>> {
+        let request = ApiRequest::get_proof_of_delivery(&shipping_id.into());
+
+        let response = self.send_pdf_request(request)?;
+        Ok(response.bytes()?.to_vec())
+    }
+
+    /// Streams the proof of delivery PDF of a shipping order into `writer`,
+    /// returning the number of bytes written.
+    pub fn write_proof_of_delivery<W>(
+        &self,
+        shipping_id: impl Into<String>,
+        writer: &mut W,
+    ) -> Result<u64, Error<ProofOfDeliveryError>>
+    where
+        W: Write + ?Sized,
+    {
+        let request = ApiRequest::get_proof_of_delivery(&shipping_id.into());
+
+        let mut response = self.send_pdf_request(request)?;
+        let written = std::io::copy(&mut response, writer)?;
+        writer.flush()?;
+
+        Ok(written)
+    }
+
+    pub fn get_webhooks_configuration(
+        &self,
+    ) -> Result<WebhooksConfigModel, Error<WebhookGetConfigurationError>> {
+        let request = ApiRequest::get_webhooks_configuration();
+
+        self.send_post_request(request)
+    }
+
+    pub fn set_webhooks_configuration(
+        &self,
+        webhook_config_request: WebhooksConfigModel,
+    ) -> Result<WebhooksConfigModel, Error<WebhookSetConfigurationError>> {
+        let request = ApiRequest::set_webhooks_configuration(&webhook_config_request)?;
+
+        self.send_post_request(request)
+    }
+}
+
+/// Free functions kept for backwards compatibility. They build a new
+/// [`PedidosYaBlockingClient`] on every call; prefer keeping one client around.
+pub mod webhooks_blocking {
+    use super::PedidosYaBlockingClient;
+    use crate::models::{Error as PedidosError, ShippingResponse, WebhooksConfigModel};
+
+    fn client<T>(api_key: String) -> Result<PedidosYaBlockingClient, PedidosError<T>> {
+        Ok(PedidosYaBlockingClient::try_new(api_key)?)
+    }
+
+    /// Drops the typed error body, which these functions never exposed.
+    fn untyped<E>(error: PedidosError<E>) -> PedidosError<()> {
+        match error {
+            PedidosError::Reqwest(e) => PedidosError::Reqwest(e),
+            PedidosError::Transport(e) => PedidosError::Transport(e),
+            PedidosError::Serde(e) => PedidosError::Serde(e),
+            PedidosError::Io(e) => PedidosError::Io(e),
+            PedidosError::Config(e) => PedidosError::Config(e),
+            PedidosError::ResponseError(content) => {
+                PedidosError::ResponseError(crate::models::ResponseContent {
+                    status: content.status,
+                    content: content.content,
+                    entity: None,
+                })
+            }
+            PedidosError::UnexpectedContentType {
+                status,
+                content_type,
+                body,
+            } => PedidosError::UnexpectedContentType {
+                status,
+                content_type,
+                body,
+            },
+            PedidosError::EmptyResponse { status } => PedidosError::EmptyResponse { status },
+            PedidosError::Decode {
+                status,
+                path,
+                body,
+                source,
+            } => PedidosError::Decode {
+                status,
+                path,
+                body,
+                source,
+            },
+            PedidosError::RateLimited {
+                endpoint,
+                retry_after,
+            } => PedidosError::RateLimited {
+                endpoint,
+                retry_after,
+            },
+        }
+    }
+
+    #[deprecated(note = "use `PedidosYaBlockingClient::get_webhooks_configuration`")]
+    pub fn blocking_webhook_get_webhooks_configuration(
+        api_key: String,
+    ) -> Result<WebhooksConfigModel, PedidosError<()>> {
+        client(api_key)?
+            .get_webhooks_configuration()
+            .map_err(untyped)
+    }
+
+    #[deprecated(note = "use `PedidosYaBlockingClient::set_webhooks_configuration`")]
+    pub fn blocking_webhook_set_webhooks_configuration(
+        api_key: String,
+
+        webhook_config_request: WebhooksConfigModel,
+    ) -> Result<WebhooksConfigModel, PedidosError<()>> {
+        client(api_key)?
+            .set_webhooks_configuration(webhook_config_request)
+            .map_err(untyped)
+    }
+
+    #[deprecated(note = "use `PedidosYaBlockingClient::shippings_shipping_oder_details_get`")]
+    pub fn blocking_get_orderstatus(
+        api_key: String,
+        shipping_id: String,
+    ) -> Result<ShippingResponse, PedidosError<()>> {
+        client(api_key)?
+            .shippings_shipping_oder_details_get(shipping_id)
+            .map_err(untyped)
+    }
+
+    #[deprecated(note = "use `PedidosYaBlockingClient::cancel_shipping`")]
+    pub fn blocking_cancel_shipping(
+        api_key: String,
+        shipping_id: String,
+        reason: String,
+    ) -> Result<ShippingResponse, PedidosError<()>> {
+        client(api_key)?
+            .cancel_shipping(shipping_id, reason)
+            .map_err(untyped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Error;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_free_functions_report_config_errors() {
+        let error = webhooks_blocking::blocking_get_orderstatus(
+            "token\nwith newline".to_owned(),
+            "64000".to_owned(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(error, Error::Config(ConfigError::InvalidAuthToken(_))),
+            "{error:?}"
+        );
+    }
+}
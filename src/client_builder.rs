@@ -0,0 +1,408 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use reqwest::header::{self, HeaderMap};
+
+#[cfg(feature = "blocking-client")]
+use crate::blocking_client::PedidosYaBlockingClient;
+use crate::{
+    api::ClientConfig,
+    endpoint::Endpoint,
+    rate_limit::{RateLimit, RateLimitMode, RateLimiter},
+    retry::RetryPolicy,
+};
+#[cfg(feature = "async-client")]
+use crate::{pedidosya_client::PedidosYaClient, transport::HttpTransport};
+
+pub(crate) const PEDIDOSYA_BASE_URL: &str = "https://courier-api.pedidosya.com";
+
+/// Errors raised while building a [`crate::PedidosYaClient`] or [`crate::PedidosYaBlockingClient`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The auth token contains characters that are not allowed in a header value.
+    InvalidAuthToken(header::InvalidHeaderValue),
+    /// The HTTP backend (TLS, resolver, ...) could not be initialized.
+    ClientInit(reqwest::Error),
+    /// The base path is not an absolute `http`/`https` URL.
+    InvalidBaseUrl {
+        base_path: String,
+        source: Option<url::ParseError>,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::InvalidAuthToken(_) => {
+                write!(f, "auth token is not a valid request header value")
+            }
+            ConfigError::ClientInit(e) => {
+                write!(f, "could not initialize PedidosYa API client: {e}")
+            }
+            ConfigError::InvalidBaseUrl {
+                base_path,
+                source: Some(e),
+            } => write!(f, "invalid base url `{base_path}`: {e}"),
+            ConfigError::InvalidBaseUrl {
+                base_path,
+                source: None,
+            } => write!(
+                f,
+                "invalid base url `{base_path}`: expected an http or https url"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::InvalidAuthToken(e) => Some(e),
+            ConfigError::ClientInit(e) => Some(e),
+            ConfigError::InvalidBaseUrl { source, .. } => source
+                .as_ref()
+                .map(|e| e as &(dyn std::error::Error + 'static)),
+        }
+    }
+}
+
+/// Builder for [`crate::PedidosYaClient`] and [`crate::PedidosYaBlockingClient`].
+///
+/// Allows pointing the client at a different base URL (a staging gateway or a
+/// local stand-in server) and tuning the underlying `reqwest` client.
+pub struct PedidosYaClientBuilder {
+    auth_token: String,
+    base_path: String,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    #[cfg(feature = "async-client")]
+    client: Option<reqwest::Client>,
+    #[cfg(feature = "blocking-client")]
+    blocking_client: Option<reqwest::blocking::Client>,
+    retry_policy: RetryPolicy,
+    rate_limit: Option<RateLimit>,
+    endpoint_rate_limits: HashMap<Endpoint, RateLimit>,
+    rate_limit_mode: RateLimitMode,
+}
+
+/// Redacts the auth token.
+impl fmt::Debug for PedidosYaClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PedidosYaClientBuilder")
+            .field("auth_token", &"[REDACTED]")
+            .field("base_path", &self.base_path)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("timeout", &self.timeout)
+            .field("proxy", &self.proxy)
+            .field("user_agent", &self.user_agent)
+            .field("default_headers", &self.default_headers)
+            .field("retry_policy", &self.retry_policy)
+            .field("rate_limit", &self.rate_limit)
+            .field("endpoint_rate_limits", &self.endpoint_rate_limits)
+            .field("rate_limit_mode", &self.rate_limit_mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PedidosYaClientBuilder {
+    pub fn new<S: Into<String>>(auth_token: S) -> Self {
+        Self {
+            auth_token: auth_token.into(),
+            base_path: PEDIDOSYA_BASE_URL.to_owned(),
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            #[cfg(feature = "async-client")]
+            client: None,
+            #[cfg(feature = "blocking-client")]
+            blocking_client: None,
+            retry_policy: RetryPolicy::default(),
+            rate_limit: None,
+            endpoint_rate_limits: HashMap::new(),
+            rate_limit_mode: RateLimitMode::default(),
+        }
+    }
+
+    /// Base URL every endpoint path is appended to. Defaults to the production courier API.
+    pub fn base_path<S: Into<String>>(mut self, base_path: S) -> Self {
+        self.base_path = base_path.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Timeout for establishing the connection only.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for the whole request, from connecting until the body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Headers sent with every request. The `Authorization` header is always set from the auth token.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Use a ready-made `reqwest::Client`.
+    ///
+    /// Timeouts, proxy, user agent and default headers set on this builder are ignored
+    /// in that case; the authorization header is still attached to every request.
+    #[cfg(feature = "async-client")]
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Use a ready-made `reqwest::blocking::Client` for [`Self::build_blocking`].
+    ///
+    /// As with [`Self::client`], the transport options on this builder are ignored in that case.
+    #[cfg(feature = "blocking-client")]
+    pub fn blocking_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.blocking_client = Some(client);
+        self
+    }
+
+    /// Retry policy for failed requests. Defaults to [`RetryPolicy::default`], which only
+    /// retries idempotent endpoints; use [`RetryPolicy::disabled`] to turn retries off.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Client-wide quota shared by every endpoint and every clone of the built client.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Quota for a single endpoint, applied on top of the client-wide one.
+    pub fn endpoint_rate_limit(mut self, endpoint: Endpoint, rate_limit: RateLimit) -> Self {
+        self.endpoint_rate_limits.insert(endpoint, rate_limit);
+        self
+    }
+
+    /// Whether requests wait for a permit (the default) or fail fast when a quota is exhausted.
+    pub fn rate_limit_mode(mut self, mode: RateLimitMode) -> Self {
+        self.rate_limit_mode = mode;
+        self
+    }
+
+    #[cfg(feature = "async-client")]
+    pub fn build(mut self) -> Result<PedidosYaClient, ConfigError> {
+        let client = match self.client.take() {
+            Some(client) => client,
+            None => self
+                .configure(reqwest::Client::builder())
+                .build()
+                .map_err(ConfigError::ClientInit)?,
+        };
+
+        self.build_with_transport(client)
+    }
+
+    /// Builds a [`PedidosYaClient`] sending its requests through `transport` instead of `reqwest`.
+    ///
+    /// Timeouts, proxy, user agent and default headers set on this builder only apply to
+    /// the `reqwest` transport and are ignored here.
+    #[cfg(feature = "async-client")]
+    pub fn build_with_transport<T: HttpTransport>(
+        self,
+        transport: T,
+    ) -> Result<PedidosYaClient<T>, ConfigError> {
+        Ok(PedidosYaClient::from_parts(transport, self.into_config()?))
+    }
+
+    /// Builds a [`PedidosYaBlockingClient`] from the same settings.
+    #[cfg(feature = "blocking-client")]
+    pub fn build_blocking(mut self) -> Result<PedidosYaBlockingClient, ConfigError> {
+        let client = match self.blocking_client.take() {
+            Some(client) => client,
+            None => self
+                .configure(reqwest::blocking::Client::builder())
+                .build()
+                .map_err(ConfigError::ClientInit)?,
+        };
+
+        Ok(PedidosYaBlockingClient::from_parts(
+            client,
+            self.into_config()?,
+        ))
+    }
+
+    /// Applies the transport options of this builder to a `reqwest` client builder, the
+    /// same way for the async and the blocking client.
+    fn configure<B: ReqwestBuilder>(&mut self, builder: B) -> B {
+        let mut builder = builder.default_headers(self.default_headers.clone());
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy.take() {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(user_agent) = self.user_agent.take() {
+            builder = builder.user_agent(user_agent);
+        }
+        builder
+    }
+
+    fn into_config(self) -> Result<ClientConfig, ConfigError> {
+        let mut auth_header = header::HeaderValue::from_str(self.auth_token.as_str())
+            .map_err(ConfigError::InvalidAuthToken)?;
+        auth_header.set_sensitive(true);
+
+        match url::Url::parse(&self.base_path) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(_) => {
+                return Err(ConfigError::InvalidBaseUrl {
+                    base_path: self.base_path,
+                    source: None,
+                });
+            }
+            Err(e) => {
+                return Err(ConfigError::InvalidBaseUrl {
+                    base_path: self.base_path,
+                    source: Some(e),
+                });
+            }
+        }
+
+        let rate_limiter = (self.rate_limit.is_some() || !self.endpoint_rate_limits.is_empty())
+            .then(|| {
+                RateLimiter::new(
+                    self.rate_limit,
+                    self.endpoint_rate_limits,
+                    self.rate_limit_mode,
+                )
+            });
+
+        Ok(ClientConfig {
+            base_path: self.base_path,
+            auth_header,
+            retry_policy: self.retry_policy,
+            rate_limiter,
+        })
+    }
+}
+
+/// The options [`PedidosYaClientBuilder`] sets on `reqwest::ClientBuilder` and
+/// `reqwest::blocking::ClientBuilder`, which share no trait.
+trait ReqwestBuilder: Sized {
+    fn default_headers(self, headers: HeaderMap) -> Self;
+    fn connect_timeout(self, timeout: Duration) -> Self;
+    fn timeout(self, timeout: Duration) -> Self;
+    fn proxy(self, proxy: reqwest::Proxy) -> Self;
+    fn user_agent(self, user_agent: String) -> Self;
+}
+
+macro_rules! impl_reqwest_builder {
+    ($builder:ty) => {
+        impl ReqwestBuilder for $builder {
+            fn default_headers(self, headers: HeaderMap) -> Self {
+                self.default_headers(headers)
+            }
+
+            fn connect_timeout(self, timeout: Duration) -> Self {
+                self.connect_timeout(timeout)
+            }
+
+            fn timeout(self, timeout: Duration) -> Self {
+                self.timeout(timeout)
+            }
+
+            fn proxy(self, proxy: reqwest::Proxy) -> Self {
+                self.proxy(proxy)
+            }
+
+            fn user_agent(self, user_agent: String) -> Self {
+                self.user_agent(user_agent)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "async-client")]
+impl_reqwest_builder!(reqwest::ClientBuilder);
+#[cfg(feature = "blocking-client")]
+impl_reqwest_builder!(reqwest::blocking::ClientBuilder);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_path_trailing_slash() {
+        let builder = PedidosYaClientBuilder::new("token").base_path("http://127.0.0.1:8080/");
+
+        assert_eq!(builder.base_path, "http://127.0.0.1:8080");
+    }
+
+    #[cfg(feature = "async-client")]
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let builder = PedidosYaClientBuilder::new("secret-token");
+        assert!(!format!("{builder:?}").contains("secret-token"));
+
+        let client = builder.build().unwrap();
+        assert!(!format!("{client:?}").contains("secret-token"));
+
+        let urls = crate::models::Urls {
+            url: "https://example.com/callback".to_owned(),
+            authorization_key: Some("secret-key".to_owned()),
+        };
+        let debug = format!("{urls:?}");
+        assert!(debug.contains("https://example.com/callback"));
+        assert!(!debug.contains("secret-key"));
+    }
+
+    #[cfg(feature = "async-client")]
+    #[test]
+    fn test_invalid_auth_token() {
+        let result = PedidosYaClientBuilder::new("bad\ntoken").build();
+
+        assert!(matches!(result, Err(ConfigError::InvalidAuthToken(_))));
+    }
+
+    #[cfg(feature = "async-client")]
+    #[test]
+    fn test_invalid_base_url() {
+        let result = PedidosYaClientBuilder::new("token")
+            .base_path("courier-api.pedidosya.com")
+            .build();
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidBaseUrl {
+                source: Some(_),
+                ..
+            })
+        ));
+
+        let result = PedidosYaClientBuilder::new("token")
+            .base_path("ftp://courier-api.pedidosya.com")
+            .build();
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidBaseUrl { source: None, .. })
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+/// Courier API operations exposed by the clients.
+///
+/// Used to decide which calls may be retried automatically and to key per-endpoint settings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Endpoint {
+    CheckCoverage,
+    EstimateShipping,
+    ConfirmEstimate,
+    CreateShipping,
+    GetShipping,
+    CancelShipping,
+    GetShippingTracking,
+    GetProofOfDelivery,
+    GetWebhooksConfiguration,
+    SetWebhooksConfiguration,
+}
+
+impl Endpoint {
+    /// Whether repeating the call can not create or change anything on the courier side.
+    ///
+    /// Only these endpoints are retried unless the [`crate::RetryPolicy`] explicitly
+    /// allows retrying the others.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            Self::CheckCoverage
+            | Self::EstimateShipping
+            | Self::GetShipping
+            | Self::GetShippingTracking
+            | Self::GetProofOfDelivery
+            | Self::GetWebhooksConfiguration => true,
+            Self::ConfirmEstimate
+            | Self::CreateShipping
+            | Self::CancelShipping
+            | Self::SetWebhooksConfiguration => false,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CheckCoverage => "check_coverage",
+            Self::EstimateShipping => "estimate_shipping",
+            Self::ConfirmEstimate => "confirm_estimate",
+            Self::CreateShipping => "create_shipping",
+            Self::GetShipping => "get_shipping",
+            Self::CancelShipping => "cancel_shipping",
+            Self::GetShippingTracking => "get_shipping_tracking",
+            Self::GetProofOfDelivery => "get_proof_of_delivery",
+            Self::GetWebhooksConfiguration => "get_webhooks_configuration",
+            Self::SetWebhooksConfiguration => "set_webhooks_configuration",
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
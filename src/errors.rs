@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ApiErrorCode, HttpErrorResponse};
+
+/// Accessors shared by the typed error bodies of every endpoint.
+pub trait EndpointError {
+    /// The error body returned by the API, unless it could not be decoded.
+    fn error_response(&self) -> Option<&HttpErrorResponse>;
+
+    /// HTTP status reported in the error body.
+    fn status(&self) -> Option<u16> {
+        self.error_response()?
+            .status
+            .and_then(|status| u16::try_from(status).ok())
+    }
+
+    /// Machine readable error code, such as `OUT_OF_DELIVERY_ZONE`.
+    fn api_code(&self) -> Option<&str> {
+        self.error_response()?.code.as_deref()
+    }
+
+    /// [`Self::api_code`] parsed into an [`ApiErrorCode`].
+    fn error_code(&self) -> Option<ApiErrorCode> {
+        self.error_response()?.error_code()
+    }
+
+    /// Human readable description of the error.
+    fn api_message(&self) -> Option<&str> {
+        self.error_response()?.message.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetShippingsEstimatesError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status500(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ConfirmEstimateError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status409(HttpErrorResponse),
+    Status500(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`v3_shippings_shipping_id_get`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ShippingOderDetailsError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status404(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::check_coverage`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CoverageCheckError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status500(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::create_shipping`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CreateShippingError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status500(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::cancel_shipping`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CancelShippingError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status404(HttpErrorResponse),
+    Status409(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::get_proof_of_delivery`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProofOfDeliveryError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status404(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::get_shipping_tracking`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ShippingTrackingError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    Status404(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::get_webhooks_configuration`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WebhookGetConfigurationError {
+    Status403(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`crate::PedidosYaClient::set_webhooks_configuration`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WebhookSetConfigurationError {
+    Status400(HttpErrorResponse),
+    Status403(HttpErrorResponse),
+    StatusNonExpected(HttpErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+impl From<HttpErrorResponse> for ConfirmEstimateError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(409) => Self::Status409(value),
+            Some(500) => Self::Status500(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for GetShippingsEstimatesError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(500) => Self::Status500(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for ShippingOderDetailsError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(404) => Self::Status404(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for CoverageCheckError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(500) => Self::Status500(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for CreateShippingError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(500) => Self::Status500(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for CancelShippingError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(404) => Self::Status404(value),
+            Some(409) => Self::Status409(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for ProofOfDeliveryError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(404) => Self::Status404(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for ShippingTrackingError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(404) => Self::Status404(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for WebhookGetConfigurationError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(403) => Self::Status403(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl From<HttpErrorResponse> for WebhookSetConfigurationError {
+    fn from(value: HttpErrorResponse) -> Self {
+        match value.status {
+            Some(400) => Self::Status400(value),
+            Some(403) => Self::Status403(value),
+            Some(_) => Self::StatusNonExpected(value),
+            None => Self::StatusNonExpected(value),
+        }
+    }
+}
+
+impl EndpointError for GetShippingsEstimatesError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status500(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for ConfirmEstimateError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status409(value) => Some(value),
+            Self::Status500(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for ShippingOderDetailsError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status404(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for CoverageCheckError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status500(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for CreateShippingError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status500(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for CancelShippingError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status404(value) => Some(value),
+            Self::Status409(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for ProofOfDeliveryError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status404(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for ShippingTrackingError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::Status404(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for WebhookGetConfigurationError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status403(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+impl EndpointError for WebhookSetConfigurationError {
+    fn error_response(&self) -> Option<&HttpErrorResponse> {
+        match self {
+            Self::Status400(value) => Some(value),
+            Self::Status403(value) => Some(value),
+            Self::StatusNonExpected(value) => Some(value),
+            Self::UnknownValue(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_error_accessors() {
+        let error = ConfirmEstimateError::from(HttpErrorResponse {
+            status: Some(400),
+            message: Some("Fuera de horario de servicio".to_owned()),
+            code: Some("OUT_OF_FLEET_TIME".to_owned()),
+        });
+
+        assert!(matches!(error, ConfirmEstimateError::Status400(_)));
+        assert_eq!(error.status(), Some(400));
+        assert_eq!(error.api_code(), Some("OUT_OF_FLEET_TIME"));
+        assert_eq!(error.error_code(), Some(ApiErrorCode::OutOfFleetTime));
+        assert_eq!(error.api_message(), Some("Fuera de horario de servicio"));
+
+        let error = ConfirmEstimateError::UnknownValue(serde_json::json!("oops"));
+        assert_eq!(error.status(), None);
+        assert_eq!(error.api_code(), None);
+    }
+}
@@ -1,5 +1,51 @@
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod api;
+#[cfg(feature = "blocking-client")]
+mod blocking_client;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod client_builder;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod endpoint;
+#[cfg(feature = "models")]
+mod errors;
+#[cfg(feature = "models")]
 pub mod models;
+#[cfg(feature = "async-client")]
+mod pedidosya_api;
+#[cfg(feature = "async-client")]
 mod pedidosya_client;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod rate_limit;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod retry;
+#[cfg(any(all(test, feature = "async-client"), feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "async-client")]
+mod transport;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
+#[cfg(feature = "blocking-client")]
+pub use blocking_client::PedidosYaBlockingClient;
+#[cfg(feature = "blocking-client")]
+pub use blocking_client::webhooks_blocking as PedidosYaBlocking;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use client_builder::{ConfigError, PedidosYaClientBuilder};
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use endpoint::Endpoint;
+#[cfg(feature = "models")]
+pub use errors::{
+    CancelShippingError, ConfirmEstimateError, CoverageCheckError, CreateShippingError,
+    EndpointError, GetShippingsEstimatesError, ProofOfDeliveryError, ShippingOderDetailsError,
+    ShippingTrackingError, WebhookGetConfigurationError, WebhookSetConfigurationError,
+};
+#[cfg(feature = "async-client")]
+pub use pedidosya_api::PedidosYaApi;
+#[cfg(feature = "async-client")]
 pub use pedidosya_client::PedidosYaClient;
-pub use pedidosya_client::webhooks_blocking as PedidosYaBlocking;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use rate_limit::{RateLimit, RateLimitMode};
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use retry::RetryPolicy;
+#[cfg(feature = "async-client")]
+pub use transport::HttpTransport;
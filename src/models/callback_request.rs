@@ -1,4 +1,3 @@
-use crate::models;
 use serde::{Deserialize, Serialize};
 
 /// CallbackRequest : Callback Request We could have different topics. Right now we only provide SHIPPING_STATUS but more topics will be available in future.
@@ -24,10 +23,55 @@ pub struct CallbackRequest {
 }
 
 /// Topic suscription name.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+/// Topics this version of the crate does not know about are kept in [`Topic::Unknown`].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Topic {
-    #[serde(rename = "SHIPPING_STATUS")]
     ShippingStatus,
+    /// A topic added to the API after this version of the crate.
+    Unknown(String),
+}
+
+impl Topic {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ShippingStatus => "SHIPPING_STATUS",
+            Self::Unknown(topic) => topic,
+        }
+    }
+}
+
+impl From<&str> for Topic {
+    fn from(value: &str) -> Self {
+        match value {
+            "SHIPPING_STATUS" => Self::ShippingStatus,
+            other => Self::Unknown(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for Topic {
+    fn from(value: String) -> Self {
+        match Self::from(value.as_str()) {
+            Self::Unknown(_) => Self::Unknown(value),
+            topic => topic,
+        }
+    }
+}
+
+impl From<Topic> for String {
+    fn from(value: Topic) -> Self {
+        match value {
+            Topic::Unknown(topic) => topic,
+            topic => topic.as_str().to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for Topic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 impl Default for Topic {
@@ -114,94 +158,153 @@ impl Default for CallbackShippingStatus {
 
 /// CancelCode : This field is setted only if status is CANCELLED. The Cancel Reason Codes are show bellow with its spanish messages for cancelReason field: * ADDRESS_DATA_MISSING: Rider no encuentra el pickup/dropoff  * NO_RIDER_AVAILABLE: No hay cadete disponible en este momento  * OUT_OF_DELIVERY_ZONE: Fuera de área de cobertura del servicio  * DELAYED_DELIVERY_SCHEDULE: Cancelado debido a horario de entrega retrasado  * COORDINATE_ERROR: Coordenadas no concuerdan con la dirección ingresada  * PACKAGE_DAMAGE_LOOSE: Se produjo un problema con el producto o paquete  * ORDER_NOT_DELIVERED: Pedido no entregado  * INAPPROPRIATE_CONDUCT: Cancelado por problemas con el rider  * UNREACHABLE_RIDER: Cancelado por problemas con el rider  * TYC_PACKAGE_CONTRADICTION: Pedido incorrecto. Paquete o producto no respeta TyC.  * PURCHASE_REQUESTED: Pedido realizado por error  * USER_CANNOT_PAY: Solicitud de envío pendiente de pago. El usuario no puede pagar el pedido.  * COUPON_NOT_APPLIED: No fue posible aplicar el cupón.  * DUPLICATED_ORDER: Pedido duplicado  * UNREACHABLE_USER_DROPOFF: No es posible contactar al cliente en Punto de Entrega  * SUSPICIOUS_CLIENT: Pedido incorrecto. * USER_CANCELLED: Cancelado a solicitud del usuario  * TECHNICAL_PROBLEM: Cancelado por problemas técnicos  * BAD_WEATHER: Condiciones climáticas adversas  * UNREACHABLE_USER_PICKUP: No es posible contactar al cliente en Punto de Retiro  * CONTENT_WRONG: Producto despachado no es correcto.  * ORDER_MODIFICATION: No es posible modificar punto de origen o destino  * OUT_OF_FLEET_TIME: Fuera de horario de servicio  * TEST_ORDER: Orden de prueba - TEST  * CONTENT_WRONG_RIDER: Producto despachado no es correcto
 /// This field is setted only if status is CANCELLED. The Cancel Reason Codes are show bellow with its spanish messages for cancelReason field: * ADDRESS_DATA_MISSING: Rider no encuentra el pickup/dropoff  * NO_RIDER_AVAILABLE: No hay cadete disponible en este momento  * OUT_OF_DELIVERY_ZONE: Fuera de área de cobertura del servicio  * DELAYED_DELIVERY_SCHEDULE: Cancelado debido a horario de entrega retrasado  * COORDINATE_ERROR: Coordenadas no concuerdan con la dirección ingresada  * PACKAGE_DAMAGE_LOOSE: Se produjo un problema con el producto o paquete  * ORDER_NOT_DELIVERED: Pedido no entregado  * INAPPROPRIATE_CONDUCT: Cancelado por problemas con el rider  * UNREACHABLE_RIDER: Cancelado por problemas con el rider  * TYC_PACKAGE_CONTRADICTION: Pedido incorrecto. Paquete o producto no respeta TyC.  * PURCHASE_REQUESTED: Pedido realizado por error  * USER_CANNOT_PAY: Solicitud de envío pendiente de pago. El usuario no puede pagar el pedido.  * COUPON_NOT_APPLIED: No fue posible aplicar el cupón.  * DUPLICATED_ORDER: Pedido duplicado  * UNREACHABLE_USER_DROPOFF: No es posible contactar al cliente en Punto de Entrega  * SUSPICIOUS_CLIENT: Pedido incorrecto. * USER_CANCELLED: Cancelado a solicitud del usuario  * TECHNICAL_PROBLEM: Cancelado por problemas técnicos  * BAD_WEATHER: Condiciones climáticas adversas  * UNREACHABLE_USER_PICKUP: No es posible contactar al cliente en Punto de Retiro  * CONTENT_WRONG: Producto despachado no es correcto.  * ORDER_MODIFICATION: No es posible modificar punto de origen o destino  * OUT_OF_FLEET_TIME: Fuera de horario de servicio  * TEST_ORDER: Orden de prueba - TEST  * CONTENT_WRONG_RIDER: Producto despachado no es correcto
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+/// Codes this version of the crate does not know about are kept in [`CancelCode::Unknown`].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum CancelCode {
-    #[serde(rename = "ADDRESS_DATA_MISSING")]
     AddressDataMissing,
-    #[serde(rename = "NO_RIDER_AVAILABLE")]
     NoRiderAvailable,
-    #[serde(rename = "OUT_OF_DELIVERY_ZONE")]
     OutOfDeliveryZone,
-    #[serde(rename = "DELAYED_DELIVERY_SCHEDULE")]
     DelayedDeliverySchedule,
-    #[serde(rename = "COORDINATE_ERROR")]
     CoordinateError,
-    #[serde(rename = "PACKAGE_DAMAGE_LOOSE")]
     PackageDamageLoose,
-    #[serde(rename = "ORDER_NOT_DELIVERED")]
     OrderNotDelivered,
-    #[serde(rename = "INAPPROPRIATE_CONDUCT")]
     InappropriateConduct,
-    #[serde(rename = "UNREACHABLE_RIDER")]
     UnreachableRider,
-    #[serde(rename = "TYC_PACKAGE_CONTRADICTION")]
     TycPackageContradiction,
-    #[serde(rename = "PURCHASE_REQUESTED")]
     PurchaseRequested,
-    #[serde(rename = "USER_CANNOT_PAY")]
     UserCannotPay,
-    #[serde(rename = "COUPON_NOT_APPLIED")]
     CouponNotApplied,
-    #[serde(rename = "DUPLICATED_ORDER")]
     DuplicatedOrder,
-    #[serde(rename = "UNREACHABLE_USER_DROPOFF")]
     UnreachableUserDropoff,
-    #[serde(rename = "SUSPICIOUS_CLIENT")]
     SuspiciousClient,
-    #[serde(rename = "USER_CANCELLED")]
     UserCancelled,
-    #[serde(rename = "TECHNICAL_PROBLEM")]
     TechnicalProblem,
-    #[serde(rename = "BAD_WEATHER")]
     BadWeather,
-    #[serde(rename = "UNREACHABLE_USER_PICKUP")]
     UnreachableUserPickup,
-    #[serde(rename = "CONTENT_WRONG")]
     ContentWrong,
-    #[serde(rename = "ORDER_MODIFICATION")]
     OrderModification,
-    #[serde(rename = "OUT_OF_FLEET_TIME")]
     OutOfFleetTime,
-    #[serde(rename = "TEST_ORDER")]
     TestOrder,
-    #[serde(rename = "CONTENT_WRONG_RIDER")]
     ContentWrongRider,
+    /// A cancel code added to the API after this version of the crate.
+    Unknown(String),
 }
 
-impl std::fmt::Display for CancelCode {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl CancelCode {
+    pub fn as_str(&self) -> &str {
         match self {
-            Self::AddressDataMissing => write!(f, "ADDRESS_DATA_MISSING"),
-            Self::NoRiderAvailable => write!(f, "NO_RIDER_AVAILABLE"),
-            Self::OutOfDeliveryZone => write!(f, "OUT_OF_DELIVERY_ZONE"),
-            Self::DelayedDeliverySchedule => write!(f, "DELAYED_DELIVERY_SCHEDULE"),
-            Self::CoordinateError => write!(f, "COORDINATE_ERROR"),
-            Self::PackageDamageLoose => write!(f, "PACKAGE_DAMAGE_LOOSE"),
-            Self::OrderNotDelivered => write!(f, "ORDER_NOT_DELIVERED"),
-            Self::InappropriateConduct => write!(f, "INAPPROPRIATE_CONDUCT"),
-            Self::UnreachableRider => write!(f, "UNREACHABLE_RIDER"),
-            Self::TycPackageContradiction => write!(f, "TYC_PACKAGE_CONTRADICTION"),
-            Self::PurchaseRequested => write!(f, "PURCHASE_REQUESTED"),
-            Self::UserCannotPay => write!(f, "USER_CANNOT_PAY"),
-            Self::CouponNotApplied => write!(f, "COUPON_NOT_APPLIED"),
-            Self::DuplicatedOrder => write!(f, "DUPLICATED_ORDER"),
-            Self::UnreachableUserDropoff => write!(f, "UNREACHABLE_USER_DROPOFF"),
-            Self::SuspiciousClient => write!(f, "SUSPICIOUS_CLIENT"),
-            Self::UserCancelled => write!(f, "USER_CANCELLED"),
-            Self::TechnicalProblem => write!(f, "TECHNICAL_PROBLEM"),
-            Self::BadWeather => write!(f, "BAD_WEATHER"),
-            Self::UnreachableUserPickup => write!(f, "UNREACHABLE_USER_PICKUP"),
-            Self::ContentWrong => write!(f, "CONTENT_WRONG"),
-            Self::OrderModification => write!(f, "ORDER_MODIFICATION"),
-            Self::OutOfFleetTime => write!(f, "OUT_OF_FLEET_TIME"),
-            Self::TestOrder => write!(f, "TEST_ORDER"),
-            Self::ContentWrongRider => write!(f, "CONTENT_WRONG_RIDER"),
+            Self::AddressDataMissing => "ADDRESS_DATA_MISSING",
+            Self::NoRiderAvailable => "NO_RIDER_AVAILABLE",
+            Self::OutOfDeliveryZone => "OUT_OF_DELIVERY_ZONE",
+            Self::DelayedDeliverySchedule => "DELAYED_DELIVERY_SCHEDULE",
+            Self::CoordinateError => "COORDINATE_ERROR",
+            Self::PackageDamageLoose => "PACKAGE_DAMAGE_LOOSE",
+            Self::OrderNotDelivered => "ORDER_NOT_DELIVERED",
+            Self::InappropriateConduct => "INAPPROPRIATE_CONDUCT",
+            Self::UnreachableRider => "UNREACHABLE_RIDER",
+            Self::TycPackageContradiction => "TYC_PACKAGE_CONTRADICTION",
+            Self::PurchaseRequested => "PURCHASE_REQUESTED",
+            Self::UserCannotPay => "USER_CANNOT_PAY",
+            Self::CouponNotApplied => "COUPON_NOT_APPLIED",
+            Self::DuplicatedOrder => "DUPLICATED_ORDER",
+            Self::UnreachableUserDropoff => "UNREACHABLE_USER_DROPOFF",
+            Self::SuspiciousClient => "SUSPICIOUS_CLIENT",
+            Self::UserCancelled => "USER_CANCELLED",
+            Self::TechnicalProblem => "TECHNICAL_PROBLEM",
+            Self::BadWeather => "BAD_WEATHER",
+            Self::UnreachableUserPickup => "UNREACHABLE_USER_PICKUP",
+            Self::ContentWrong => "CONTENT_WRONG",
+            Self::OrderModification => "ORDER_MODIFICATION",
+            Self::OutOfFleetTime => "OUT_OF_FLEET_TIME",
+            Self::TestOrder => "TEST_ORDER",
+            Self::ContentWrongRider => "CONTENT_WRONG_RIDER",
+            Self::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<&str> for CancelCode {
+    fn from(value: &str) -> Self {
+        match value {
+            "ADDRESS_DATA_MISSING" => Self::AddressDataMissing,
+            "NO_RIDER_AVAILABLE" => Self::NoRiderAvailable,
+            "OUT_OF_DELIVERY_ZONE" => Self::OutOfDeliveryZone,
+            "DELAYED_DELIVERY_SCHEDULE" => Self::DelayedDeliverySchedule,
+            "COORDINATE_ERROR" => Self::CoordinateError,
+            "PACKAGE_DAMAGE_LOOSE" => Self::PackageDamageLoose,
+            "ORDER_NOT_DELIVERED" => Self::OrderNotDelivered,
+            "INAPPROPRIATE_CONDUCT" => Self::InappropriateConduct,
+            "UNREACHABLE_RIDER" => Self::UnreachableRider,
+            "TYC_PACKAGE_CONTRADICTION" => Self::TycPackageContradiction,
+            "PURCHASE_REQUESTED" => Self::PurchaseRequested,
+            "USER_CANNOT_PAY" => Self::UserCannotPay,
+            "COUPON_NOT_APPLIED" => Self::CouponNotApplied,
+            "DUPLICATED_ORDER" => Self::DuplicatedOrder,
+            "UNREACHABLE_USER_DROPOFF" => Self::UnreachableUserDropoff,
+            "SUSPICIOUS_CLIENT" => Self::SuspiciousClient,
+            "USER_CANCELLED" => Self::UserCancelled,
+            "TECHNICAL_PROBLEM" => Self::TechnicalProblem,
+            "BAD_WEATHER" => Self::BadWeather,
+            "UNREACHABLE_USER_PICKUP" => Self::UnreachableUserPickup,
+            "CONTENT_WRONG" => Self::ContentWrong,
+            "ORDER_MODIFICATION" => Self::OrderModification,
+            "OUT_OF_FLEET_TIME" => Self::OutOfFleetTime,
+            "TEST_ORDER" => Self::TestOrder,
+            "CONTENT_WRONG_RIDER" => Self::ContentWrongRider,
+            other => Self::Unknown(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for CancelCode {
+    fn from(value: String) -> Self {
+        match Self::from(value.as_str()) {
+            Self::Unknown(_) => Self::Unknown(value),
+            code => code,
         }
     }
 }
 
+impl From<CancelCode> for String {
+    fn from(value: CancelCode) -> Self {
+        match value {
+            CancelCode::Unknown(code) => code,
+            code => code.as_str().to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for CancelCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 impl Default for CancelCode {
     fn default() -> CancelCode {
         Self::AddressDataMissing
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_topic() {
+        let callback: CallbackRequest =
+            serde_json::from_str(r#"{"topic": "RIDER_LOCATION", "id": "1"}"#).unwrap();
+        assert_eq!(
+            callback.topic,
+            Some(Topic::Unknown("RIDER_LOCATION".to_owned()))
+        );
+        assert_eq!(
+            serde_json::to_string(&callback).unwrap(),
+            r#"{"topic":"RIDER_LOCATION","id":"1"}"#
+        );
+
+        let topic: Topic = serde_json::from_str(r#""SHIPPING_STATUS""#).unwrap();
+        assert_eq!(topic, Topic::ShippingStatus);
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// CancelShippingRequest : This model represents the cancellation of a shipping order.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CancelShippingRequest {
+    /// Reason why the shipping order is being cancelled.
+    #[serde(rename = "reasonText")]
+    pub reason_text: String,
+}
+
+impl CancelShippingRequest {
+    /// This model represents the cancellation of a shipping order.
+    pub fn new(reason_text: String) -> CancelShippingRequest {
+        CancelShippingRequest { reason_text }
+    }
+}
@@ -8,7 +8,6 @@
  * Generated by: https://openapi-generator.tech
  */
 
-use crate::models;
 use serde::{Deserialize, Serialize};
 
 /// ConfirmedEstimationShippingRequest : This model represents an order that must be confirmed.
@@ -0,0 +1,19 @@
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CoverageRequest : Route whose coverage by the PedidosYa fleet must be checked.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CoverageRequest {
+    /// Geographical points where transport should go. There must be two waypoints, one with type PICK_UP & one DROP_OFF.
+    #[serde(rename = "waypoints")]
+    pub waypoints: Vec<models::WayPointModel>,
+}
+
+impl CoverageRequest {
+    /// Route whose coverage by the PedidosYa fleet must be checked.
+    pub fn new(pickup: models::WayPointModel, dropoff: models::WayPointModel) -> CoverageRequest {
+        CoverageRequest {
+            waypoints: vec![pickup, dropoff],
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ApiErrorCode;
+
+/// CoverageResponse : Result of a coverage check. If the route is not covered, `reasonCode` and `reason` explain why.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CoverageResponse {
+    /// Whether the PedidosYa fleet can serve the route between the pick-up and drop-off points.
+    #[serde(rename = "covered")]
+    pub covered: bool,
+    /// Reason why the route is not covered, such as [`ApiErrorCode::OutOfDeliveryZone`]. This field is set only if covered is false.
+    #[serde(rename = "reasonCode", skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<ApiErrorCode>,
+    /// Description of why the route is not covered. This field is set only if covered is false.
+    #[serde(rename = "reason", skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl CoverageResponse {
+    /// Result of a coverage check. If the route is not covered, `reasonCode` and `reason` explain why.
+    pub fn new(covered: bool) -> CoverageResponse {
+        CoverageResponse {
+            covered,
+            reason_code: None,
+            reason: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage_reason_code() {
+        let response: CoverageResponse = serde_json::from_str(
+            r#"{"covered": false, "reasonCode": "OUT_OF_FLEET_TIME", "reason": "Fuera de horario de servicio"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.reason_code, Some(ApiErrorCode::OutOfFleetTime));
+
+        let response: CoverageResponse =
+            serde_json::from_str(r#"{"covered": false, "reasonCode": "ROUTE_TOO_LONG"}"#).unwrap();
+        assert_eq!(
+            response.reason_code,
+            Some(ApiErrorCode::Unknown("ROUTE_TOO_LONG".to_owned()))
+        );
+    }
+}
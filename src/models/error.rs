@@ -0,0 +1,278 @@
+use std::{error, fmt};
+
+use reqwest::StatusCode;
+
+use crate::EndpointError;
+use crate::models::ApiErrorCode;
+
+#[derive(Debug)]
+pub struct ResponseContent<T> {
+    pub status: StatusCode,
+    pub content: String,
+    pub entity: Option<T>,
+}
+
+#[derive(Debug)]
+pub enum Error<T> {
+    Reqwest(reqwest::Error),
+    /// Error raised by a custom [`crate::HttpTransport`] or while reading its response body.
+    Transport(Box<dyn error::Error + Send + Sync>),
+    Serde(serde_json::Error),
+    Io(std::io::Error),
+    /// The client could not be built, e.g. because of an invalid auth token or base URL.
+    Config(crate::ConfigError),
+    ResponseError(ResponseContent<T>),
+    /// The response carries a body in a format the endpoint does not answer with,
+    /// such as the HTML error page of a gateway.
+    UnexpectedContentType {
+        status: StatusCode,
+        content_type: Option<String>,
+        body: String,
+    },
+    /// A successful response came without the expected body.
+    EmptyResponse {
+        status: StatusCode,
+    },
+    /// A successful JSON response does not match the expected model. `path` points at the
+    /// failing field, e.g. `deliveryOffers[0].pricing.total`.
+    Decode {
+        status: StatusCode,
+        path: String,
+        body: String,
+        source: serde_json::Error,
+    },
+    /// The client-side rate limit for `endpoint` is exhausted and the client is set to fail fast.
+    RateLimited {
+        endpoint: crate::Endpoint,
+        retry_after: std::time::Duration,
+    },
+}
+impl<T> fmt::Display for Error<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (module, e) = match self {
+            Error::Reqwest(e) => ("reqwest", e.to_string()),
+            Error::Transport(e) => ("transport", e.to_string()),
+            Error::Serde(e) => ("serde", e.to_string()),
+            Error::Io(e) => ("IO", e.to_string()),
+            Error::Config(e) => ("config", e.to_string()),
+            Error::ResponseError(e) => ("response", format!("status code {}", e.status)),
+            Error::UnexpectedContentType {
+                status,
+                content_type,
+                ..
+            } => (
+                "response",
+                format!(
+                    "status code {status} with unexpected content type `{}`",
+                    content_type.as_deref().unwrap_or("none")
+                ),
+            ),
+            Error::EmptyResponse { status } => {
+                ("response", format!("status code {status} with empty body"))
+            }
+            Error::Decode { path, source, .. } => ("serde", format!("{source} at `{path}`")),
+            Error::RateLimited {
+                endpoint,
+                retry_after,
+            } => (
+                "rate limit",
+                format!("quota for `{endpoint}` exhausted, retry after {retry_after:?}"),
+            ),
+        };
+        write!(f, "error in {module}: {e}")
+    }
+}
+
+impl<T: fmt::Debug> error::Error for Error<T> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Reqwest(e) => Some(e),
+            Error::Transport(e) => Some(e.as_ref()),
+            Error::Serde(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Config(e) => Some(e),
+            Error::Decode { source, .. } => Some(source),
+            Error::ResponseError(_)
+            | Error::UnexpectedContentType { .. }
+            | Error::EmptyResponse { .. }
+            | Error::RateLimited { .. } => None,
+        }
+    }
+}
+
+impl<T> Error<T> {
+    /// HTTP status of the response, if the request got one.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::ResponseError(content) => Some(content.status),
+            Error::UnexpectedContentType { status, .. }
+            | Error::EmptyResponse { status }
+            | Error::Decode { status, .. } => Some(*status),
+            Error::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again may succeed: transport failures, client-side
+    /// rate limiting and the statuses retried by [`crate::RetryPolicy::default`].
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Reqwest(e) if crate::retry::is_transient_error(e) => true,
+            Error::Transport(e) if crate::retry::is_transient_error(e.as_ref()) => true,
+            Error::RateLimited { .. } => true,
+            _ => matches!(
+                self.status(),
+                Some(
+                    StatusCode::TOO_MANY_REQUESTS
+                        | StatusCode::INTERNAL_SERVER_ERROR
+                        | StatusCode::BAD_GATEWAY
+                        | StatusCode::SERVICE_UNAVAILABLE
+                        | StatusCode::GATEWAY_TIMEOUT
+                )
+            ),
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.status() == Some(StatusCode::CONFLICT)
+    }
+
+    /// Whether the auth token was rejected or lacks permission for the operation.
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self.status(),
+            Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+        )
+    }
+
+    /// Wraps an error of an [`crate::HttpTransport`], keeping `reqwest` errors in [`Error::Reqwest`].
+    #[cfg(feature = "async-client")]
+    pub(crate) fn transport(error: impl Into<Box<dyn error::Error + Send + Sync>>) -> Self {
+        match error.into().downcast::<reqwest::Error>() {
+            Ok(e) => Error::Reqwest(*e),
+            Err(e) => Error::Transport(e),
+        }
+    }
+}
+
+impl<T: EndpointError> Error<T> {
+    /// Machine readable error code from the API error body, such as `OUT_OF_DELIVERY_ZONE`.
+    pub fn api_code(&self) -> Option<&str> {
+        self.entity()?.api_code()
+    }
+
+    /// [`Self::api_code`] parsed into an [`ApiErrorCode`], to branch on without string matching.
+    pub fn error_code(&self) -> Option<ApiErrorCode> {
+        self.entity()?.error_code()
+    }
+
+    /// Human readable description from the API error body.
+    pub fn api_message(&self) -> Option<&str> {
+        self.entity()?.api_message()
+    }
+
+    fn entity(&self) -> Option<&T> {
+        match self {
+            Error::ResponseError(content) => content.entity.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<reqwest::Error> for Error<T> {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Reqwest(e)
+    }
+}
+
+impl<T> From<std::io::Error> for Error<T> {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl<T> From<crate::ConfigError> for Error<T> {
+    fn from(value: crate::ConfigError) -> Self {
+        Error::Config(value)
+    }
+}
+
+impl<T> From<serde_json::Error> for Error<T> {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Serde(value)
+    }
+}
+
+pub enum ContentType {
+    Json,
+    Pdf,
+    Unsoported(String),
+    None,
+}
+
+impl From<Option<&str>> for ContentType {
+    fn from(value: Option<&str>) -> Self {
+        match value {
+            Some(text) => {
+                if text.starts_with("application/json") {
+                    ContentType::Json
+                } else if text.starts_with("application/pdf") {
+                    ContentType::Pdf
+                } else {
+                    ContentType::Unsoported(text.to_owned())
+                }
+            }
+            None => ContentType::None,
+        }
+    }
+}
+
+impl ContentType {
+    /// The content type as sent in the `Content-Type` header, if any.
+    pub fn into_header(self) -> Option<String> {
+        match self {
+            ContentType::Json => Some("application/json".to_owned()),
+            ContentType::Pdf => Some("application/pdf".to_owned()),
+            ContentType::Unsoported(text) => Some(text),
+            ContentType::None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::ShippingOderDetailsError;
+    use crate::models::HttpErrorResponse;
+
+    use super::*;
+
+    #[test]
+    fn test_error_accessors() {
+        let error: Error<ShippingOderDetailsError> = Error::ResponseError(ResponseContent {
+            status: StatusCode::NOT_FOUND,
+            content: String::new(),
+            entity: Some(ShippingOderDetailsError::Status404(HttpErrorResponse {
+                status: Some(404),
+                message: Some("Shipping not found".to_owned()),
+                code: Some("SHIPPING_NOT_FOUND".to_owned()),
+            })),
+        });
+
+        assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(error.api_code(), Some("SHIPPING_NOT_FOUND"));
+        assert_eq!(error.api_message(), Some("Shipping not found"));
+        assert!(error.is_not_found());
+        assert!(!error.is_retryable());
+        assert_eq!(
+            error.to_string(),
+            "error in response: status code 404 Not Found"
+        );
+
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(boxed.source().is_none());
+    }
+}
@@ -8,6 +8,8 @@
  * Generated by: https://openapi-generator.tech
  */
 
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 /// HttpResponse : HTTP response
@@ -33,4 +35,92 @@ impl HttpErrorResponse {
             code: None,
         }
     }
+
+    /// The `code` field parsed into an [`ApiErrorCode`].
+    pub fn error_code(&self) -> Option<ApiErrorCode> {
+        self.code.as_deref().map(ApiErrorCode::from)
+    }
+}
+
+/// Error codes returned by the courier API in [`HttpErrorResponse::code`], as documented for
+/// the reason codes of the API. Other codes are kept in [`ApiErrorCode::Unknown`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ApiErrorCode {
+    /// The rider can not find the pickup or dropoff address.
+    AddressDataMissing,
+    /// A point of the route is outside of the PedidosYa delivery zones.
+    OutOfDeliveryZone,
+    /// The coordinates do not match the given address.
+    CoordinateError,
+    /// The fleet does not operate at the requested time.
+    OutOfFleetTime,
+    /// A code this version of the crate does not know about.
+    Unknown(String),
+}
+
+impl ApiErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::AddressDataMissing => "ADDRESS_DATA_MISSING",
+            Self::OutOfDeliveryZone => "OUT_OF_DELIVERY_ZONE",
+            Self::CoordinateError => "COORDINATE_ERROR",
+            Self::OutOfFleetTime => "OUT_OF_FLEET_TIME",
+            Self::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<&str> for ApiErrorCode {
+    fn from(value: &str) -> Self {
+        match value {
+            "ADDRESS_DATA_MISSING" => Self::AddressDataMissing,
+            "OUT_OF_DELIVERY_ZONE" => Self::OutOfDeliveryZone,
+            "COORDINATE_ERROR" => Self::CoordinateError,
+            "OUT_OF_FLEET_TIME" => Self::OutOfFleetTime,
+            other => Self::Unknown(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for ApiErrorCode {
+    fn from(value: String) -> Self {
+        match Self::from(value.as_str()) {
+            Self::Unknown(_) => Self::Unknown(value),
+            code => code,
+        }
+    }
+}
+
+impl From<ApiErrorCode> for String {
+    fn from(value: ApiErrorCode) -> Self {
+        match value {
+            ApiErrorCode::Unknown(code) => code,
+            code => code.as_str().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code() {
+        let response: HttpErrorResponse = serde_json::from_str(
+            r#"{"status": 400, "message": "Fuera de área de cobertura del servicio", "code": "OUT_OF_DELIVERY_ZONE"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.error_code(), Some(ApiErrorCode::OutOfDeliveryZone));
+
+        let code: ApiErrorCode = serde_json::from_str(r#""SOMETHING_NEW""#).unwrap();
+        assert_eq!(code, ApiErrorCode::Unknown("SOMETHING_NEW".to_owned()));
+        assert_eq!(serde_json::to_string(&code).unwrap(), r#""SOMETHING_NEW""#);
+    }
 }
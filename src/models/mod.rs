@@ -1,53 +1,8 @@
-#[derive(Debug)]
-pub struct ResponseContent<T> {
-    pub status: reqwest::StatusCode,
-    pub content: String,
-    pub entity: Option<T>,
-}
-
-#[derive(Debug)]
-pub enum Error<T> {
-    Reqwest(reqwest::Error),
-    Serde(serde_json::Error),
-    Io(std::io::Error),
-    ResponseError(ResponseContent<T>),
-}
-impl<T> From<reqwest::Error> for Error<T> {
-    fn from(e: reqwest::Error) -> Self {
-        Error::Reqwest(e)
-    }
-}
-
-impl<T> From<serde_json::Error> for Error<T> {
-    fn from(value: serde_json::Error) -> Self {
-        Error::Serde(value)
-    }
-}
-
-pub enum ContentType {
-    Json,
-    Pdf,
-    Unsoported(String),
-    None,
-}
-
-impl From<Option<&str>> for ContentType {
-    fn from(value: Option<&str>) -> Self {
-        match value {
-            Some(text) => {
-                if text.starts_with("application/json") {
-                    ContentType::Json
-                } else if text.starts_with("application/pdf") {
-                    ContentType::Pdf
-                } else {
-                    ContentType::Unsoported(text.to_owned())
-                }
-            }
-            None => ContentType::None,
-        }
-    }
-}
+// The models follow the openapi-generator layout, which spells out `Default`
+// impls for enums instead of deriving them.
+#![allow(clippy::derivable_impls)]
 
+pub mod cancel_shipping_request;
 pub mod confirm_estime_order_request;
 pub mod confirm_shipping_order_response;
 pub mod estimation_shipping_request;
@@ -60,22 +15,39 @@ pub mod webhook_configuration;
 pub mod webhook_configuration_model;
 
 mod callback_request;
+mod coverage_request;
+mod coverage_response;
 mod delivery_offer;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod error;
+mod rider;
+mod rider_location;
 mod route;
 mod shipping_item_request;
 mod shipping_pricing_route;
 mod shipping_request_requirements;
 mod shipping_response;
+mod shipping_tracking_response;
 mod way_point_model;
 mod way_point_model_response;
 
 pub use callback_request::CallbackRequest;
+pub use callback_request::CancelCode;
+pub use callback_request::{CallbackRequestData, CallbackShippingStatus, Topic as CallbackTopic};
+pub use cancel_shipping_request::CancelShippingRequest;
 pub use confirm_estime_order_request::ConfirmEstimationShippingRequest;
 pub use confirm_shipping_order_response::ConfirmShippingResponse;
-pub use delivery_offer::DeliveryOffer;
+pub use coverage_request::CoverageRequest;
+pub use coverage_response::CoverageResponse;
+pub use delivery_offer::{DeliveryMode, DeliveryOffer};
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use error::{ContentType, Error, ResponseContent};
 pub use estimation_shipping_request::EstimationShippingRequest;
 pub use estimation_shipping_response::EstimationShippingResponse;
-pub use http_errors::HttpErrorResponse;
+pub use http_errors::{ApiErrorCode, HttpErrorResponse};
+pub use rider::Rider;
+pub use rider::VehicleType;
+pub use rider_location::RiderLocation;
 pub use route::Route;
 pub use shipping_item_request::ShippingItemRequest;
 pub use shipping_pricing_route::ShippingRoutePricing;
@@ -83,6 +55,7 @@ pub use shipping_request_requirements::ShippingRequestRequirements;
 pub use shipping_response::ShippingResponse;
 pub use shipping_route::ShippingRoute;
 pub use shipping_status::ShippingStatus;
+pub use shipping_tracking_response::ShippingTrackingResponse;
 pub use urls::Urls;
 pub use way_point_model::Type as WayPointModelType;
 pub use way_point_model::WayPointModel;
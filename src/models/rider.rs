@@ -0,0 +1,116 @@
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// Rider : Rider assigned to the shipping order.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rider {
+    /// Rider's first name.
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Rider's contact telephone number.
+    #[serde(rename = "phone", skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(rename = "vehicleType", skip_serializing_if = "Option::is_none")]
+    pub vehicle_type: Option<VehicleType>,
+    #[serde(rename = "location", skip_serializing_if = "Option::is_none")]
+    pub location: Option<models::RiderLocation>,
+}
+
+impl Rider {
+    /// Rider assigned to the shipping order.
+    pub fn new() -> Rider {
+        Rider {
+            name: None,
+            phone: None,
+            vehicle_type: None,
+            location: None,
+        }
+    }
+}
+/// Vehicle used by the rider to transport the shipping order.
+/// Vehicle types this version of the crate does not know about are kept in [`VehicleType::Unknown`].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum VehicleType {
+    Bicycle,
+    Motorcycle,
+    Car,
+    Walker,
+    Unknown(String),
+}
+
+impl VehicleType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Bicycle => "BICYCLE",
+            Self::Motorcycle => "MOTORCYCLE",
+            Self::Car => "CAR",
+            Self::Walker => "WALKER",
+            Self::Unknown(vehicle_type) => vehicle_type,
+        }
+    }
+}
+
+impl From<&str> for VehicleType {
+    fn from(value: &str) -> Self {
+        match value {
+            "BICYCLE" => Self::Bicycle,
+            "MOTORCYCLE" => Self::Motorcycle,
+            "CAR" => Self::Car,
+            "WALKER" => Self::Walker,
+            other => Self::Unknown(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for VehicleType {
+    fn from(value: String) -> Self {
+        match Self::from(value.as_str()) {
+            Self::Unknown(_) => Self::Unknown(value),
+            vehicle_type => vehicle_type,
+        }
+    }
+}
+
+impl From<VehicleType> for String {
+    fn from(value: VehicleType) -> Self {
+        match value {
+            VehicleType::Unknown(vehicle_type) => vehicle_type,
+            vehicle_type => vehicle_type.as_str().to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for VehicleType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Default for VehicleType {
+    fn default() -> VehicleType {
+        Self::Motorcycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_vehicle_type() {
+        let rider: Rider =
+            serde_json::from_str(r#"{"name": "Juan", "vehicleType": "SCOOTER"}"#).unwrap();
+        assert_eq!(
+            rider.vehicle_type,
+            Some(VehicleType::Unknown("SCOOTER".to_owned()))
+        );
+        assert_eq!(
+            serde_json::to_string(&rider.vehicle_type).unwrap(),
+            r#""SCOOTER""#
+        );
+
+        let vehicle_type: VehicleType = serde_json::from_str(r#""CAR""#).unwrap();
+        assert_eq!(vehicle_type, VehicleType::Car);
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// RiderLocation : Last known geographical position of the rider.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RiderLocation {
+    /// Geographical point latitude.
+    #[serde(rename = "latitude")]
+    pub latitude: f64,
+    /// Geographical point longitude.
+    #[serde(rename = "longitude")]
+    pub longitude: f64,
+}
+
+impl RiderLocation {
+    /// Last known geographical position of the rider.
+    pub fn new(latitude: f64, longitude: f64) -> RiderLocation {
+        RiderLocation {
+            latitude,
+            longitude,
+        }
+    }
+}
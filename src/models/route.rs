@@ -8,7 +8,6 @@
  * Generated by: https://openapi-generator.tech
  */
 
-use crate::models;
 use serde::{Deserialize, Serialize};
 
 /// Route : Info related to waypoints geographic analysis.
@@ -8,7 +8,6 @@
  * Generated by: https://openapi-generator.tech
  */
 
-use crate::models;
 use serde::{Deserialize, Serialize};
 
 /// ShippingRoutePricing : Delivery offer pricing information
@@ -1,4 +1,4 @@
-use crate::models::{self, ShippingStatus};
+use crate::models::{self, CancelCode, ShippingStatus};
 use serde::{Deserialize, Serialize};
 
 /// ShippingResponse : This model represents a shipping estimate order.
@@ -32,6 +32,12 @@ pub struct ShippingResponse {
     pub status: Option<ShippingStatus>,
     #[serde(rename = "shareLocationUrl", skip_serializing_if = "Option::is_none")]
     pub share_location_url: Option<String>,
+    /// Cancellation code. This field is set only if status is CANCELLED.
+    #[serde(rename = "cancelCode", skip_serializing_if = "Option::is_none")]
+    pub cancel_code: Option<CancelCode>,
+    /// Spanish message text for the cancel code. This field is set only if status is CANCELLED.
+    #[serde(rename = "cancelReason", skip_serializing_if = "Option::is_none")]
+    pub cancel_reason: Option<String>,
 }
 
 impl ShippingResponse {
@@ -48,6 +54,34 @@ impl ShippingResponse {
             notification_mail: None,
             status: None,
             share_location_url: None,
+            cancel_code: None,
+            cancel_reason: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_cancel_code() {
+        let shipping: ShippingResponse = serde_json::from_str(
+            r#"{"shippingId": "64000", "status": "CANCELLED", "cancelCode": "STORE_CLOSED", "cancelReason": "Local cerrado"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            shipping.cancel_code,
+            Some(CancelCode::Unknown("STORE_CLOSED".to_owned()))
+        );
+        assert_eq!(shipping.cancel_reason.as_deref(), Some("Local cerrado"));
+        assert!(
+            serde_json::to_string(&shipping)
+                .unwrap()
+                .contains(r#""cancelCode":"STORE_CLOSED""#)
+        );
+
+        let code: CancelCode = serde_json::from_str(r#""BAD_WEATHER""#).unwrap();
+        assert_eq!(code, CancelCode::BadWeather);
+    }
+}
@@ -8,7 +8,6 @@
  * Generated by: https://openapi-generator.tech
  */
 
-use crate::models;
 use serde::{Deserialize, Serialize};
 
 /// ShippingStatus : Shippings status. Status values are: * Rejected: Shipping order requested but rejected due to invalid data * Confirmed: Shipping order confirmed and awaiting for dispatching  * In Progress: Transport has been assigned   * Near Pickup: Transport is near pickup point  * Picked up: Transport picked up the order's items  * Near Dropoff: Transport is closest to dropoff point  * Completed: Transport had delivered the items  * Cancelled: Shipping order cancelled for any reason. <br><br>
@@ -0,0 +1,30 @@
+use crate::models::{self, ShippingStatus};
+use serde::{Deserialize, Serialize};
+
+/// ShippingTrackingResponse : Live tracking information of a shipping order.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShippingTrackingResponse {
+    /// Shipping identifier
+    #[serde(rename = "shippingId", skip_serializing_if = "Option::is_none")]
+    pub shipping_id: Option<String>,
+    #[serde(rename = "status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<ShippingStatus>,
+    /// Rider assigned to the shipping. This field is set once a transport has been assigned.
+    #[serde(rename = "rider", skip_serializing_if = "Option::is_none")]
+    pub rider: Option<Box<models::Rider>>,
+    /// Date time (in UTC) of the last tracking update. Format ISO 8601: YYYY-MM-DDTHH:MM:SSZ
+    #[serde(rename = "lastUpdate", skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<String>,
+}
+
+impl ShippingTrackingResponse {
+    /// Live tracking information of a shipping order.
+    pub fn new() -> ShippingTrackingResponse {
+        ShippingTrackingResponse {
+            shipping_id: None,
+            status: None,
+            rider: None,
+            last_update: None,
+        }
+    }
+}
@@ -8,9 +8,11 @@
  * Generated by: https://openapi-generator.tech
  */
 
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Urls {
     /// It must be a valid URL. You can't use these characters: <, >
     #[serde(rename = "url")]
@@ -28,3 +30,16 @@ impl Urls {
         }
     }
 }
+
+/// Redacts `authorization_key`, so webhook configurations can be logged safely.
+impl fmt::Debug for Urls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Urls")
+            .field("url", &self.url)
+            .field(
+                "authorization_key",
+                &self.authorization_key.as_ref().map(|_| "[REDACTED]"),
+            )
+            .finish()
+    }
+}
@@ -87,6 +87,29 @@ impl WayPointModelResponse {
         }
     }
 }
+impl From<models::WayPointModel> for WayPointModelResponse {
+    fn from(value: models::WayPointModel) -> Self {
+        WayPointModelResponse {
+            address_street: value.address_street,
+            address_additional: value.address_additional,
+            latitude: value.latitude,
+            longitude: value.longitude,
+            phone: value.phone,
+            name: value.name,
+            instructions: value.instructions,
+            city: value.city,
+            r#type: match value.r#type {
+                models::WayPointModelType::PickUp => Type::PickUp,
+                models::WayPointModelType::DropOff => Type::DropOff,
+            },
+            collect_money: value.collect_money,
+            pay_money: None,
+            collect_delivery_fee_money: None,
+            pay_delivery_fee_money: None,
+        }
+    }
+}
+
 /// What transport should do in that point. Only 1 Pickup & 1 Dropoff is allowed to use
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum Type {
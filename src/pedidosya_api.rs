@@ -0,0 +1,111 @@
+use std::future::Future;
+
+use crate::errors::{
+    CancelShippingError, ConfirmEstimateError, CreateShippingError, GetShippingsEstimatesError,
+    ShippingOderDetailsError, WebhookGetConfigurationError, WebhookSetConfigurationError,
+};
+use crate::models::{
+    ConfirmEstimationShippingRequest, ConfirmShippingResponse, Error, EstimationShippingRequest,
+    EstimationShippingResponse, ShippingResponse, WebhooksConfigModel,
+};
+use crate::pedidosya_client::PedidosYaClient;
+use crate::transport::HttpTransport;
+
+/// The shipping lifecycle and webhook configuration endpoints of the courier API.
+///
+/// Implemented by [`PedidosYaClient`]. Code depending on this trait instead of the concrete
+/// client can be tested against the in-memory fake of the `testing` feature.
+pub trait PedidosYaApi: Send + Sync {
+    fn shipping_estimate_shipping_order(
+        &self,
+        estimation_shipping_request: EstimationShippingRequest,
+    ) -> impl Future<Output = Result<EstimationShippingResponse, Error<GetShippingsEstimatesError>>> + Send;
+
+    fn shipping_confirm_estimate_order(
+        &self,
+        estimate_id: String,
+        confirm_estimate_request: ConfirmEstimationShippingRequest,
+    ) -> impl Future<Output = Result<ConfirmShippingResponse, Error<ConfirmEstimateError>>> + Send;
+
+    fn create_shipping(
+        &self,
+        shipping_request: EstimationShippingRequest,
+    ) -> impl Future<Output = Result<ConfirmShippingResponse, Error<CreateShippingError>>> + Send;
+
+    fn shippings_shipping_oder_details_get(
+        &self,
+        shipping_id: String,
+    ) -> impl Future<Output = Result<ShippingResponse, Error<ShippingOderDetailsError>>> + Send;
+
+    fn cancel_shipping(
+        &self,
+        shipping_id: String,
+        reason: String,
+    ) -> impl Future<Output = Result<ShippingResponse, Error<CancelShippingError>>> + Send;
+
+    fn get_webhooks_configuration(
+        &self,
+    ) -> impl Future<Output = Result<WebhooksConfigModel, Error<WebhookGetConfigurationError>>> + Send;
+
+    fn set_webhooks_configuration(
+        &self,
+        webhook_config_request: WebhooksConfigModel,
+    ) -> impl Future<Output = Result<WebhooksConfigModel, Error<WebhookSetConfigurationError>>> + Send;
+}
+
+impl<T: HttpTransport> PedidosYaApi for PedidosYaClient<T> {
+    async fn shipping_estimate_shipping_order(
+        &self,
+        estimation_shipping_request: EstimationShippingRequest,
+    ) -> Result<EstimationShippingResponse, Error<GetShippingsEstimatesError>> {
+        PedidosYaClient::shipping_estimate_shipping_order(self, estimation_shipping_request).await
+    }
+
+    async fn shipping_confirm_estimate_order(
+        &self,
+        estimate_id: String,
+        confirm_estimate_request: ConfirmEstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<ConfirmEstimateError>> {
+        PedidosYaClient::shipping_confirm_estimate_order(
+            self,
+            estimate_id,
+            confirm_estimate_request,
+        )
+        .await
+    }
+
+    async fn create_shipping(
+        &self,
+        shipping_request: EstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<CreateShippingError>> {
+        PedidosYaClient::create_shipping(self, shipping_request).await
+    }
+
+    async fn shippings_shipping_oder_details_get(
+        &self,
+        shipping_id: String,
+    ) -> Result<ShippingResponse, Error<ShippingOderDetailsError>> {
+        PedidosYaClient::shippings_shipping_oder_details_get(self, shipping_id).await
+    }
+
+    async fn cancel_shipping(
+        &self,
+        shipping_id: String,
+        reason: String,
+    ) -> Result<ShippingResponse, Error<CancelShippingError>> {
+        PedidosYaClient::cancel_shipping(self, shipping_id, reason).await
+    }
+
+    async fn get_webhooks_configuration(
+        &self,
+    ) -> Result<WebhooksConfigModel, Error<WebhookGetConfigurationError>> {
+        PedidosYaClient::get_webhooks_configuration(self).await
+    }
+
+    async fn set_webhooks_configuration(
+        &self,
+        webhook_config_request: WebhooksConfigModel,
+    ) -> Result<WebhooksConfigModel, Error<WebhookSetConfigurationError>> {
+        PedidosYaClient::set_webhooks_configuration(self, webhook_config_request).await
+    }
+}
@@ -1,175 +1,196 @@
-use reqwest::{
-    Request,
-    header::{self, AUTHORIZATION},
+use std::time::Instant;
+
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::Instrument;
+
+use crate::api::{self, ApiRequest, ClientConfig};
+use crate::client_builder::{ConfigError, PedidosYaClientBuilder};
+use crate::errors::{
+    CancelShippingError, ConfirmEstimateError, CoverageCheckError, CreateShippingError,
+    GetShippingsEstimatesError, ProofOfDeliveryError, ShippingOderDetailsError,
+    ShippingTrackingError, WebhookGetConfigurationError, WebhookSetConfigurationError,
 };
-use serde::{Deserialize, Serialize, de::Error as _};
-
 use crate::models::{
-    ConfirmEstimationShippingRequest, ConfirmShippingResponse, ContentType, Error,
-    EstimationShippingResponse, HttpErrorResponse, ResponseContent, ShippingResponse,
+    ConfirmEstimationShippingRequest, ConfirmShippingResponse, ContentType, CoverageResponse,
+    Error, EstimationShippingResponse, HttpErrorResponse, ShippingResponse,
+    ShippingTrackingResponse, WayPointModel, WebhooksConfigModel,
     estimation_shipping_request::EstimationShippingRequest,
 };
+use crate::transport::HttpTransport;
 
-const PEDIDOSYA_BASE_URL: &str = "https://courier-api.pedidosya.com";
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum GetShippingsEstimatesError {
-    Status400(HttpErrorResponse),
-    Status403(HttpErrorResponse),
-    Status500(HttpErrorResponse),
-    StatusNonExpected(HttpErrorResponse),
-    UnknownValue(serde_json::Value),
+/// Async client for the PedidosYa Courier API.
+///
+/// Requests go through `T`, a [`HttpTransport`], which is a `reqwest::Client` unless the
+/// client is built with [`PedidosYaClientBuilder::build_with_transport`].
+#[derive(Debug, Clone)]
+pub struct PedidosYaClient<T = reqwest::Client> {
+    transport: T,
+    config: ClientConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum ConfirmEstimateError {
-    Status400(HttpErrorResponse),
-    Status403(HttpErrorResponse),
-    Status409(HttpErrorResponse),
-    Status500(HttpErrorResponse),
-    StatusNonExpected(HttpErrorResponse),
-    UnknownValue(serde_json::Value),
-}
+impl PedidosYaClient {
+    /// Creates a client against the production API.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client cannot be built, see [`PedidosYaClient::try_new`].
+    pub fn new<S: Into<String>>(auth_token: S) -> Self {
+        Self::try_new(auth_token)
+            .expect("Error: could not initialize PedidosYa API client. Please try again!")
+    }
 
-/// struct for typed errors of method [`v3_shippings_shipping_id_get`]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum ShippingOderDetailsError {
-    Status400(HttpErrorResponse),
-    Status403(HttpErrorResponse),
-    Status404(HttpErrorResponse),
-    StatusNonExpected(HttpErrorResponse),
-    UnknownValue(serde_json::Value),
-}
-impl From<HttpErrorResponse> for ConfirmEstimateError {
-    fn from(value: HttpErrorResponse) -> Self {
-        match value.status {
-            Some(400) => Self::Status400(value),
-            Some(403) => Self::Status403(value),
-            Some(409) => Self::Status409(value),
-            Some(500) => Self::Status500(value),
-            Some(_) => Self::StatusNonExpected(value),
-            None => Self::StatusNonExpected(value),
-        }
+    pub fn try_new<S: Into<String>>(auth_token: S) -> Result<Self, ConfigError> {
+        PedidosYaClientBuilder::new(auth_token).build()
     }
-}
 
-impl From<HttpErrorResponse> for GetShippingsEstimatesError {
-    fn from(value: HttpErrorResponse) -> Self {
-        match value.status {
-            Some(400) => Self::Status400(value),
-            Some(403) => Self::Status403(value),
-            Some(500) => Self::Status500(value),
-            Some(_) => Self::StatusNonExpected(value),
-            None => Self::StatusNonExpected(value),
-        }
+    pub fn builder<S: Into<String>>(auth_token: S) -> PedidosYaClientBuilder {
+        PedidosYaClientBuilder::new(auth_token)
     }
 }
 
-impl From<HttpErrorResponse> for ShippingOderDetailsError {
-    fn from(value: HttpErrorResponse) -> Self {
-        match value.status {
-            Some(400) => Self::Status400(value),
-            Some(403) => Self::Status403(value),
-            Some(404) => Self::Status404(value),
-            Some(_) => Self::StatusNonExpected(value),
-            None => Self::StatusNonExpected(value),
-        }
+impl<T: HttpTransport> PedidosYaClient<T> {
+    pub(crate) fn from_parts(transport: T, config: ClientConfig) -> Self {
+        Self { transport, config }
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct PedidosYaClient {
-    client: reqwest::Client,
-    base_path: String,
-    auth_token: String,
-}
+    /// The transport requests are sent through.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
 
-impl PedidosYaClient {
-    pub fn new<S: Into<String>>(auth_token: S) -> Self {
-        let auth_token: String = auth_token.into();
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            header::HeaderValue::from_str(auth_token.as_str())
-                .expect("Error: could not parse auth token into a valid request header."),
-        );
+    fn build_request(&self, request: &ApiRequest) -> Result<http::Request<Bytes>, http::Error> {
+        let mut builder = http::Request::builder()
+            .method(request.method.clone())
+            .uri(self.config.url(request))
+            .header(AUTHORIZATION, self.config.auth_header.clone());
+        if request.body.is_some() {
+            builder = builder.header(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+        builder.body(request.body.clone().map(Bytes::from).unwrap_or_default())
+    }
 
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()
-            .expect("Error: could not initialize PedidosYa API client. Please try again!");
+    /// Executes `request`, retrying it as allowed by the client's [`crate::RetryPolicy`].
+    /// Every attempt takes its own rate limit permit.
+    ///
+    /// Runs inside the span of [`ApiRequest::span`], recording the final status, attempt
+    /// number and latency on it.
+    async fn execute<E>(&self, request: &ApiRequest) -> Result<http::Response<T::Body>, Error<E>> {
+        let span = tracing::Span::current();
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            while let Some(wait) = self.config.try_permit(request.endpoint)? {
+                tracing::debug!(?wait, "waiting for a rate limit permit");
+                tokio::time::sleep(wait).await;
+            }
 
-        Self {
-            client,
-            base_path: PEDIDOSYA_BASE_URL.to_owned(),
-            auth_token,
+            let http_request = self.build_request(request).map_err(Error::transport)?;
+            let attempt_started = Instant::now();
+            let result = self.transport.send(http_request).await.map_err(Into::into);
+            let latency_ms = attempt_started.elapsed().as_millis() as u64;
+
+            let outcome = match &result {
+                Ok(response) => {
+                    tracing::debug!(
+                        attempt,
+                        status = response.status().as_u16(),
+                        latency_ms,
+                        "response received"
+                    );
+                    Ok((response.status(), response.headers()))
+                }
+                Err(e) => {
+                    tracing::debug!(attempt, latency_ms, error = %e, "request failed");
+                    Err(e.as_ref() as &(dyn std::error::Error + 'static))
+                }
+            };
+            match self
+                .config
+                .retry_policy
+                .retry_delay(request.endpoint, attempt, outcome)
+            {
+                Some(delay) => {
+                    tracing::debug!(attempt, ?delay, "retrying request");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => {
+                    span.record("attempt", attempt);
+                    span.record("latency_ms", started.elapsed().as_millis() as u64);
+                    if let Ok(response) = &result {
+                        span.record("status", response.status().as_u16());
+                    }
+                    return result.map_err(Error::transport);
+                }
+            }
         }
     }
 
-    async fn send_post_request<Res, E>(&self, request: Request) -> Result<Res, Error<E>>
+    async fn send_post_request<Res, E>(&self, request: ApiRequest) -> Result<Res, Error<E>>
     where
         Res: serde::de::DeserializeOwned,
         E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
     {
-        let response = self.client.execute(request).await?;
-
-        let content_type: ContentType = response
-            .headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .into();
-        let status = response.status();
-
-        if response.status().is_success() {
-            match content_type {
-                ContentType::Json => Ok(response.json().await?),
-                ContentType::Pdf => Err(Error::Serde(serde_json::Error::custom(
-                    "Received `application/pdf` content type response that cannot be converted to `models::`",
-                ))),
-                ContentType::Unsoported(txt) => {
-                    Err(Error::Serde(serde_json::Error::custom(format!(
-                        "Received `{txt}` content type response that cannot be converted to `models::`"
-                    ))))
-                }
-                ContentType::None => Err(Error::Serde(serde_json::Error::custom(
-                    "Received empty content type response that cannot be converted to `models::`",
-                ))),
+        let span = request.span();
+        async {
+            let response = self.execute(&request).await?;
+
+            let status = response.status();
+            let content_type = api::content_type(response.headers());
+            let body = read_body(response).await?;
+
+            api::decode_json(status, content_type, &body)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Like [`Self::send_post_request`] but for endpoints answering with a PDF document.
+    /// The successful response is returned untouched so the body can be buffered or streamed.
+    async fn send_pdf_request<E>(
+        &self,
+        request: ApiRequest,
+    ) -> Result<http::Response<T::Body>, Error<E>>
+    where
+        E: serde::de::DeserializeOwned + From<HttpErrorResponse>,
+    {
+        let span = request.span();
+        async {
+            let response = self.execute(&request).await?;
+
+            let status = response.status();
+            let content_type = api::content_type(response.headers());
+
+            if status.is_success() && matches!(content_type, ContentType::Pdf) {
+                return Ok(response);
             }
-        } else if let ContentType::Json = content_type {
-            let content = response.text().await?;
-            let entity: Option<E> = serde_json::from_str::<HttpErrorResponse>(&content)
-                .ok()
-                .map(E::from);
-
-            let content = ResponseContent {
-                status,
-                content,
-                entity,
-            };
-            Err(Error::ResponseError(content))
-        } else {
-            Err(Error::Serde(serde_json::Error::custom(
-                "Received empty content type response that cannot be converted to `models::`",
-            )))
+            let body = read_body(response).await?;
+            Err(api::pdf_error(status, content_type, &body))
         }
+        .instrument(span)
+        .await
+    }
+
+    /// Checks whether the route between `pickup` and `dropoff` is served by the PedidosYa fleet,
+    /// so uncovered addresses can be rejected before requesting an estimate.
+    pub async fn check_coverage(
+        &self,
+        pickup: WayPointModel,
+        dropoff: WayPointModel,
+    ) -> Result<CoverageResponse, Error<CoverageCheckError>> {
+        let request = ApiRequest::check_coverage(pickup, dropoff)?;
+
+        self.send_post_request(request).await
     }
 
     pub async fn shipping_estimate_shipping_order(
         &self,
         estimation_shipping_request: EstimationShippingRequest,
     ) -> Result<EstimationShippingResponse, Error<GetShippingsEstimatesError>> {
-        let url_path = "/v3/shippings/estimates";
-        let request = self
-            .client
-            .request(
-                reqwest::Method::POST,
-                format!("{}{}", self.base_path, url_path),
-            )
-            .json(&estimation_shipping_request)
-            .build()?;
+        let request = ApiRequest::estimate_shipping(&estimation_shipping_request)?;
 
         self.send_post_request(request).await
     }
@@ -179,15 +200,17 @@ impl PedidosYaClient {
         estimate_id: impl Into<String>,
         confirm_estimate_request: ConfirmEstimationShippingRequest,
     ) -> Result<ConfirmShippingResponse, Error<ConfirmEstimateError>> {
-        let url_path = format!("/v3/shippings/estimates/{}/confirm", estimate_id.into());
-        let request = self
-            .client
-            .request(
-                reqwest::Method::POST,
-                format!("{}{}", self.base_path, url_path),
-            )
-            .json(&confirm_estimate_request)
-            .build()?;
+        let request = ApiRequest::confirm_estimate(&estimate_id.into(), &confirm_estimate_request)?;
+
+        self.send_post_request(request).await
+    }
+
+    /// Creates and confirms a shipping order in a single call, without going through an estimate.
+    pub async fn create_shipping(
+        &self,
+        shipping_request: EstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<CreateShippingError>> {
+        let request = ApiRequest::create_shipping(&shipping_request)?;
 
         self.send_post_request(request).await
     }
@@ -196,83 +219,290 @@ impl PedidosYaClient {
         &self,
         shipping_id: impl Into<String>,
     ) -> Result<ShippingResponse, Error<ShippingOderDetailsError>> {
-        let url_path = format!("/v3/shippings/{}", shipping_id.into());
-
-        let request = self
-            .client
-            .request(
-                reqwest::Method::GET,
-                format!("{}{}", self.base_path, url_path),
-            )
-            .build()?;
+        let request = ApiRequest::get_shipping(&shipping_id.into());
 
         self.send_post_request(request).await
     }
-}
 
-pub enum WebhookGetConfigurationError {
-    Status403(HttpErrorResponse),
-}
-pub mod webhooks_blocking {
-    use crate::models::{Error as PedidosError, ShippingResponse, WebhooksConfigModel};
+    /// Cancels a shipping order. The returned order carries the resulting `cancel_code`.
+    pub async fn cancel_shipping(
+        &self,
+        shipping_id: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Result<ShippingResponse, Error<CancelShippingError>> {
+        let request = ApiRequest::cancel_shipping(&shipping_id.into(), reason.into())?;
 
-    pub fn blocking_webhook_get_webhooks_configuration(
-        api_key: String,
-    ) -> Result<WebhooksConfigModel, PedidosError<()>> {
-        let uri = "https://courier-api.pedidosya.com/v3/webhooks-configuration";
+        self.send_post_request(request).await
+    }
 
-        let client = reqwest::blocking::Client::new();
+    /// Live tracking of a shipping order: rider position, vehicle and contact details.
+    pub async fn get_shipping_tracking(
+        &self,
+        shipping_id: impl Into<String>,
+    ) -> Result<ShippingTrackingResponse, Error<ShippingTrackingError>> {
+        let request = ApiRequest::get_shipping_tracking(&shipping_id.into());
 
-        client
-            .get(uri)
-            .header(reqwest::header::AUTHORIZATION, api_key)
-            .send()
-            .inspect(|r| println!("{}", r.status()))
-            .and_then(|r| r.json::<WebhooksConfigModel>())
-            .map_err(PedidosError::from)
+        self.send_post_request(request).await
     }
-    pub fn blocking_webhook_set_webhooks_configuration(
-        api_key: String,
 
-        webhook_config_request: WebhooksConfigModel,
-    ) -> Result<WebhooksConfigModel, PedidosError<()>> {
-        let uri = "https://courier-api.pedidosya.com/v3/webhooks-configuration";
+    /// Downloads the proof of delivery (signature or photo) of a shipping order as a PDF document.
+    pub async fn get_proof_of_delivery(
+        &self,
+        shipping_id: impl Into<String>,
+    ) -> Result<Vec<u8>, Error<ProofOfDeliveryError>> {
+        let request = ApiRequest::get_proof_of_delivery(&shipping_id.into());
 
-        let client = reqwest::blocking::Client::new();
+        let response = self.send_pdf_request(request).await?;
+        Ok(read_body(response).await?.to_vec())
+    }
 
-        client
-            .put(uri)
-            .header(reqwest::header::AUTHORIZATION, api_key)
-            .json(&webhook_config_request)
-            .send()
-            .and_then(|res| res.json::<WebhooksConfigModel>())
-            .map_err(PedidosError::from)
+    /// Streams the proof of delivery PDF of a shipping order into `writer`,
+    /// returning the number of bytes written.
+    pub async fn write_proof_of_delivery<W>(
+        &self,
+        shipping_id: impl Into<String>,
+        writer: &mut W,
+    ) -> Result<u64, Error<ProofOfDeliveryError>>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let request = ApiRequest::get_proof_of_delivery(&shipping_id.into());
+
+        let response = self.send_pdf_request(request).await?;
+        let mut body = std::pin::pin!(response.into_body());
+        let mut written = 0;
+        while let Some(frame) = body.frame().await {
+            let Ok(chunk) = frame.map_err(Error::transport)?.into_data() else {
+                continue;
+            };
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
     }
 
-    pub fn blocking_get_orderstatus(
-        api_key: String,
-        shipping_id: String,
-    ) -> Result<ShippingResponse, PedidosError<()>> {
-        let uri = format!("https://courier-api.pedidosya.com/v3/shippings/{shipping_id}");
+    pub async fn get_webhooks_configuration(
+        &self,
+    ) -> Result<WebhooksConfigModel, Error<WebhookGetConfigurationError>> {
+        let request = ApiRequest::get_webhooks_configuration();
 
-        let client = reqwest::blocking::Client::new();
+        self.send_post_request(request).await
+    }
 
-        client
-            .get(uri)
-            .header(reqwest::header::AUTHORIZATION, api_key)
-            .send()
-            .inspect(|r| println!("{}", r.status()))
-            .and_then(|r| r.json::<ShippingResponse>())
-            .map_err(PedidosError::from)
+    pub async fn set_webhooks_configuration(
+        &self,
+        webhook_config_request: WebhooksConfigModel,
+    ) -> Result<WebhooksConfigModel, Error<WebhookSetConfigurationError>> {
+        let request = ApiRequest::set_webhooks_configuration(&webhook_config_request)?;
+
+        self.send_post_request(request).await
     }
 }
+
+async fn read_body<B, E>(response: http::Response<B>) -> Result<Bytes, Error<E>>
+where
+    B: http_body::Body<Data = Bytes, Error: Into<Box<dyn std::error::Error + Send + Sync>>>,
+{
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(Error::transport)?;
+    Ok(body.to_bytes())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{collections::VecDeque, convert::Infallible, sync::Mutex, time::Duration};
+
+    use http_body_util::Full;
+
     use crate::models::WebhooksConfigModel;
+    use crate::{EndpointError, RetryPolicy};
 
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
 
+    /// Answers with queued responses and records the requests it was sent.
+    #[derive(Default)]
+    struct CannedTransport {
+        responses: Mutex<VecDeque<http::Response<Full<Bytes>>>>,
+        requests: Mutex<Vec<http::Request<Bytes>>>,
+    }
+
+    impl CannedTransport {
+        fn respond(self, status: u16, content_type: &str, body: &'static str) -> Self {
+            let response = http::Response::builder()
+                .status(status)
+                .header(CONTENT_TYPE, content_type)
+                .body(Full::new(Bytes::from_static(body.as_bytes())))
+                .unwrap();
+            self.responses.lock().unwrap().push_back(response);
+            self
+        }
+    }
+
+    impl HttpTransport for CannedTransport {
+        type Body = Full<Bytes>;
+        type Error = Infallible;
+
+        async fn send(
+            &self,
+            request: http::Request<Bytes>,
+        ) -> Result<http::Response<Self::Body>, Self::Error> {
+            self.requests.lock().unwrap().push(request);
+            Ok(self.responses.lock().unwrap().pop_front().unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport_with_retry() {
+        let transport = CannedTransport::default()
+            .respond(503, "text/html", "<html>Service Unavailable</html>")
+            .respond(
+                200,
+                "application/json",
+                r#"{"shippingId": "64000", "status": "CONFIRMED"}"#,
+            );
+        let client = PedidosYaClient::builder("token")
+            .base_path("http://courier.test")
+            .retry_policy(
+                RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(1)),
+            )
+            .build_with_transport(transport)
+            .unwrap();
+
+        let shipping = client
+            .shippings_shipping_oder_details_get("64000")
+            .await
+            .unwrap();
+        assert_eq!(shipping.shipping_id.as_deref(), Some("64000"));
+
+        let requests = client.transport().requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].uri().to_string(),
+            "http://courier.test/v3/shippings/64000"
+        );
+        assert_eq!(requests[0].headers()[AUTHORIZATION], "token");
+    }
+
+    type Fields = Vec<(String, String)>;
+
+    /// Subscriber keeping the name and fields of every span and event.
+    #[derive(Default)]
+    struct CapturingSubscriber {
+        spans: Mutex<Vec<(&'static tracing::Metadata<'static>, Fields)>>,
+        events: Mutex<Vec<Fields>>,
+        entered: Mutex<Vec<tracing::span::Id>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.push((field.name().to_owned(), value.to_owned()));
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push((field.name().to_owned(), format!("{value:?}")));
+        }
+    }
+
+    impl tracing::Subscriber for &'static CapturingSubscriber {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Vec::new();
+            span.record(&mut FieldVisitor(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut FieldVisitor(
+                &mut spans[span.into_u64() as usize - 1].1,
+            ));
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = Vec::new();
+            event.record(&mut FieldVisitor(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, span: &tracing::span::Id) {
+            self.entered.lock().unwrap().push(span.clone());
+        }
+
+        fn exit(&self, _: &tracing::span::Id) {
+            self.entered.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> tracing_core::span::Current {
+            match self.entered.lock().unwrap().last() {
+                Some(span) => {
+                    let metadata = self.spans.lock().unwrap()[span.into_u64() as usize - 1].0;
+                    tracing_core::span::Current::new(span.clone(), metadata)
+                }
+                None => tracing_core::span::Current::none(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_span() {
+        let subscriber: &'static CapturingSubscriber = Box::leak(Box::default());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let transport = CannedTransport::default().respond(
+            200,
+            "application/json",
+            r#"{"shippingId": "64000", "status": "CONFIRMED"}"#,
+        );
+        let client = PedidosYaClient::builder("secret-token")
+            .base_path("http://courier.test")
+            .build_with_transport(transport)
+            .unwrap();
+        client
+            .shippings_shipping_oder_details_get("64000")
+            .await
+            .unwrap();
+
+        let spans = subscriber.spans.lock().unwrap();
+        let (metadata, fields) = &spans[0];
+        assert_eq!(metadata.name(), "pedidosya_request");
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(field("endpoint"), Some("get_shipping"));
+        assert_eq!(field("method"), Some("GET"));
+        assert_eq!(field("shipping_id"), Some("64000"));
+        assert_eq!(field("status"), Some("200"));
+        assert_eq!(field("attempt"), Some("1"));
+
+        let events = subscriber.events.lock().unwrap();
+        assert!(!events.is_empty());
+        let recorded = spans
+            .iter()
+            .flat_map(|(_, fields)| fields)
+            .chain(events.iter().flatten());
+        for (_, value) in recorded {
+            assert!(!value.contains("secret-token"), "token leaked in {value}");
+        }
+    }
+
     #[test]
     fn test_serialization() {
         let j = "
@@ -300,4 +530,33 @@ mod tests {
 
         println!("{:?}", v)
     }
+
+    #[test]
+    fn test_error_body_decoding() {
+        let j = r#"{"status": 403, "message": "Menlo Park, CA", "code": "Menlo Park, CA"}"#;
+
+        let v = serde_json::from_str::<GetShippingsEstimatesError>(j).unwrap();
+        assert_eq!(v.status(), Some(403));
+        assert_eq!(v.api_message(), Some("Menlo Park, CA"));
+
+        assert!(serde_json::from_str::<WebhooksConfigModel>(j).is_err());
+    }
+
+    #[test]
+    fn test_cancelled_shipping_response() {
+        let j = "
+        {
+            \"shippingId\": \"64000\",
+            \"status\": \"CANCELLED\",
+            \"cancelCode\": \"USER_CANCELLED\",
+            \"cancelReason\": \"Cancelado a solicitud del usuario\"
+        }";
+
+        let v = serde_json::from_str::<ShippingResponse>(j).unwrap();
+
+        assert_eq!(
+            v.cancel_code,
+            Some(crate::models::CancelCode::UserCancelled)
+        );
+    }
 }
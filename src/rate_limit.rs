@@ -0,0 +1,249 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::endpoint::Endpoint;
+
+/// Token bucket quota: `requests` permits per `per`, with bursts up to `burst` requests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    per: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allows `requests` requests every `per`. The burst defaults to `requests`.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let requests = requests.max(1);
+        Self {
+            requests,
+            per,
+            burst: requests,
+        }
+    }
+
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Maximum number of requests that can be sent back to back after an idle period.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+}
+
+/// What the client does when no permit is available.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum RateLimitMode {
+    /// Wait until a permit becomes available.
+    #[default]
+    Wait,
+    /// Return [`crate::models::Error::RateLimited`] immediately.
+    FailFast,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    /// Longest time a single token takes to refill.
+    per: Duration,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            capacity: f64::from(limit.burst),
+            tokens: f64::from(limit.burst),
+            refill_per_sec: f64::from(limit.requests) / limit.per.as_secs_f64(),
+            per: limit.per,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Time until a whole token is available, zero if there is one already. Capped at
+    /// `per`, as extreme quotas make the computed time overflow a `Duration`.
+    fn wait_time(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        Duration::try_from_secs_f64((1.0 - self.tokens) / self.refill_per_sec)
+            .map_or(self.per, |wait| wait.min(self.per))
+    }
+}
+
+#[derive(Debug)]
+struct Limits {
+    global: Option<Mutex<TokenBucket>>,
+    endpoints: HashMap<Endpoint, Mutex<TokenBucket>>,
+    mode: RateLimitMode,
+}
+
+/// Client-side rate limiter. Cloning it shares the underlying buckets, so every
+/// clone of a client draws from the same quota.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    limits: Arc<Limits>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(
+        global: Option<RateLimit>,
+        endpoints: HashMap<Endpoint, RateLimit>,
+        mode: RateLimitMode,
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            limits: Arc::new(Limits {
+                global: global.map(|limit| Mutex::new(TokenBucket::new(limit, now))),
+                endpoints: endpoints
+                    .into_iter()
+                    .map(|(endpoint, limit)| (endpoint, Mutex::new(TokenBucket::new(limit, now))))
+                    .collect(),
+                mode,
+            }),
+        }
+    }
+
+    pub(crate) fn mode(&self) -> RateLimitMode {
+        self.limits.mode
+    }
+
+    /// Takes a permit for `endpoint` from both the client-wide and the endpoint bucket.
+    /// On failure nothing is consumed and the time until a permit is available is returned.
+    pub(crate) fn try_acquire(&self, endpoint: Endpoint) -> Result<(), Duration> {
+        self.try_acquire_at(endpoint, Instant::now())
+    }
+
+    fn try_acquire_at(&self, endpoint: Endpoint, now: Instant) -> Result<(), Duration> {
+        let mut global = self
+            .limits
+            .global
+            .as_ref()
+            .map(|bucket| bucket.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut endpoint = self
+            .limits
+            .endpoints
+            .get(&endpoint)
+            .map(|bucket| bucket.lock().unwrap_or_else(|e| e.into_inner()));
+
+        let mut wait = Duration::ZERO;
+        for bucket in [global.as_deref_mut(), endpoint.as_deref_mut()]
+            .into_iter()
+            .flatten()
+        {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_time());
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        for bucket in [global.as_deref_mut(), endpoint.as_deref_mut()]
+            .into_iter()
+            .flatten()
+        {
+            bucket.tokens -= 1.0;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = RateLimiter::new(
+            Some(RateLimit::per_second(2)),
+            HashMap::new(),
+            RateLimitMode::FailFast,
+        );
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(Endpoint::GetShipping, now).is_ok());
+        assert!(limiter.try_acquire_at(Endpoint::GetShipping, now).is_ok());
+        let wait = limiter
+            .try_acquire_at(Endpoint::GetShipping, now)
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at(Endpoint::GetShipping, later).is_ok());
+    }
+
+    #[test]
+    fn test_endpoint_limit_does_not_consume_global_on_denial() {
+        let limiter = RateLimiter::new(
+            Some(RateLimit::per_second(10)),
+            HashMap::from([(Endpoint::EstimateShipping, RateLimit::per_minute(1))]),
+            RateLimitMode::FailFast,
+        );
+        let now = Instant::now();
+
+        assert!(
+            limiter
+                .try_acquire_at(Endpoint::EstimateShipping, now)
+                .is_ok()
+        );
+        for _ in 0..5 {
+            assert!(
+                limiter
+                    .try_acquire_at(Endpoint::EstimateShipping, now)
+                    .is_err()
+            );
+        }
+        for _ in 0..9 {
+            assert!(limiter.try_acquire_at(Endpoint::GetShipping, now).is_ok());
+        }
+        assert!(limiter.try_acquire_at(Endpoint::GetShipping, now).is_err());
+    }
+
+    #[test]
+    fn test_clones_share_buckets() {
+        let limiter = RateLimiter::new(
+            Some(RateLimit::per_minute(1)),
+            HashMap::new(),
+            RateLimitMode::FailFast,
+        );
+        let clone = limiter.clone();
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(Endpoint::GetShipping, now).is_ok());
+        assert!(clone.try_acquire_at(Endpoint::GetShipping, now).is_err());
+    }
+
+    #[test]
+    fn test_extreme_quota() {
+        let limiter = RateLimiter::new(
+            Some(RateLimit::new(1, Duration::MAX)),
+            HashMap::new(),
+            RateLimitMode::FailFast,
+        );
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(Endpoint::GetShipping, now).is_ok());
+        let wait = limiter
+            .try_acquire_at(Endpoint::GetShipping, now)
+            .unwrap_err();
+        assert_eq!(wait, Duration::MAX);
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    error::Error,
+    io,
+    time::{Duration, SystemTime},
+};
+
+use reqwest::{StatusCode, header::HeaderMap};
+
+use crate::endpoint::Endpoint;
+
+/// Retry policy applied by the client to failed requests.
+///
+/// Requests are retried on connection errors, timeouts and on the configured
+/// status codes, waiting an exponential backoff with jitter between attempts.
+/// A `Retry-After` header on the response takes precedence over the backoff.
+///
+/// Only idempotent endpoints (see [`Endpoint::is_idempotent`]) are retried by
+/// default; confirmations, creations and cancellations are retried only when
+/// [`RetryPolicy::retry_non_idempotent`] is enabled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    max_retry_after: Duration,
+    retry_statuses: Vec<StatusCode>,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+            max_retry_after: Duration::from_secs(60),
+            retry_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy that never retries.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Total number of attempts, including the first one. Values below 1 are treated as 1.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Backoff before the first retry, doubled on every further retry up to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Longest `Retry-After` the client is willing to wait. Responses asking for more are returned as is.
+    pub fn max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// Response status codes that are considered transient.
+    pub fn retry_statuses(mut self, statuses: Vec<StatusCode>) -> Self {
+        self.retry_statuses = statuses;
+        self
+    }
+
+    /// Allow retrying endpoints that are not idempotent, such as estimate confirmations.
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Delay before retrying attempt number `attempt` (starting at 1) of `endpoint`, given the
+    /// response status and headers or the transport error. `None` means the outcome is final.
+    pub(crate) fn retry_delay(
+        &self,
+        endpoint: Endpoint,
+        attempt: u32,
+        outcome: Result<(StatusCode, &HeaderMap), &(dyn Error + 'static)>,
+    ) -> Option<Duration> {
+        if !self.allows_retry(endpoint, attempt) {
+            return None;
+        }
+        match outcome {
+            Ok((status, headers)) if self.is_retryable_status(status) => {
+                self.delay(attempt, Some(headers))
+            }
+            Err(e) if is_transient_error(e) => self.delay(attempt, None),
+            _ => None,
+        }
+    }
+
+    /// Whether another attempt may follow attempt number `attempt` (starting at 1) of `endpoint`.
+    fn allows_retry(&self, endpoint: Endpoint, attempt: u32) -> bool {
+        attempt < self.max_attempts && (endpoint.is_idempotent() || self.retry_non_idempotent)
+    }
+
+    fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    /// Delay before the attempt following attempt number `attempt` (starting at 1).
+    /// Returns `None` when the server asked to wait longer than allowed.
+    fn delay(&self, attempt: u32, headers: Option<&HeaderMap>) -> Option<Duration> {
+        if let Some(retry_after) = headers.and_then(retry_after) {
+            return (retry_after <= self.max_retry_after).then_some(retry_after);
+        }
+
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            // "Equal jitter": keep half of the backoff, randomize the other half.
+            let half = backoff / 2;
+            Some(half + half.mul_f64(fastrand::f64()))
+        } else {
+            Some(backoff)
+        }
+    }
+}
+
+/// Whether a transport error is worth retrying: timeouts and connection failures, either
+/// reported by `reqwest` or found as an I/O error in the error chain of another transport.
+pub(crate) fn is_transient_error(error: &(dyn Error + 'static)) -> bool {
+    if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+        return e.is_timeout() || e.is_connect() || e.is_request();
+    }
+
+    let mut source = Some(error);
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<io::Error>() {
+            return matches!(
+                e.kind(),
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::UnexpectedEof
+            );
+        }
+        source = e.source();
+    }
+    false
+}
+
+/// Parses a `Retry-After` header given either in delta-seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = RetryPolicy::new()
+            .jitter(false)
+            .backoff(Duration::from_millis(100), Duration::from_millis(350));
+
+        assert_eq!(policy.delay(1, None), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay(2, None), Some(Duration::from_millis(200)));
+        assert_eq!(policy.delay(3, None), Some(Duration::from_millis(350)));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_backoff() {
+        let policy = RetryPolicy::new().backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+        for _ in 0..100 {
+            let delay = policy.delay(1, None).unwrap();
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let policy = RetryPolicy::new().max_retry_after(Duration::from_secs(10));
+        let mut headers = HeaderMap::new();
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(
+            policy.delay(1, Some(&headers)),
+            Some(Duration::from_secs(3))
+        );
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(policy.delay(1, Some(&headers)), None);
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(policy.delay(1, Some(&headers)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_non_idempotent_endpoints_are_not_retried_by_default() {
+        let policy = RetryPolicy::new();
+
+        assert!(policy.allows_retry(Endpoint::EstimateShipping, 1));
+        assert!(!policy.allows_retry(Endpoint::EstimateShipping, 3));
+        assert!(!policy.allows_retry(Endpoint::ConfirmEstimate, 1));
+        assert!(
+            policy
+                .retry_non_idempotent(true)
+                .allows_retry(Endpoint::ConfirmEstimate, 1)
+        );
+    }
+}
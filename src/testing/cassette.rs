@@ -0,0 +1,389 @@
+use std::{
+    collections::BTreeMap,
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use reqwest::header::{AUTHORIZATION, CONTENT_LENGTH, HeaderMap, TRANSFER_ENCODING};
+use serde::{Deserialize, Serialize};
+
+use crate::HttpTransport;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// JSON fields holding secrets, redacted at any depth of recorded bodies: the webhook
+/// `authorizationKey` of the webhooks configuration.
+const REDACTED_FIELDS: [&str; 1] = ["authorizationKey"];
+
+/// Request/response pairs recorded by [`RecordingTransport`] and served by [`ReplayTransport`].
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query of the request URL, without the base URL.
+    pub path: String,
+    /// Request headers, with `Authorization` redacted.
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<RecordedBody>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<RecordedBody>,
+}
+
+/// A request or response body. JSON bodies are kept as JSON to keep cassettes readable, with
+/// the webhook `authorizationKey` fields redacted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedBody {
+    Json(serde_json::Value),
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            None
+        } else if let Ok(mut json) = serde_json::from_slice(bytes) {
+            redact(&mut json);
+            Some(RecordedBody::Json(json))
+        } else if let Ok(text) = std::str::from_utf8(bytes) {
+            Some(RecordedBody::Text(text.to_owned()))
+        } else {
+            Some(RecordedBody::Binary(bytes.to_vec()))
+        }
+    }
+
+    fn to_bytes(&self) -> Bytes {
+        match self {
+            RecordedBody::Json(json) => Bytes::from(serde_json::to_vec(json).unwrap_or_default()),
+            RecordedBody::Text(text) => Bytes::from(text.clone()),
+            RecordedBody::Binary(bytes) => Bytes::from(bytes.clone()),
+        }
+    }
+}
+
+fn redact(json: &mut serde_json::Value) {
+    match json {
+        serde_json::Value::Object(fields) => {
+            for (name, value) in fields {
+                if REDACTED_FIELDS.contains(&name.as_str()) && !value.is_null() {
+                    *value = serde_json::Value::from(REDACTED);
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read(path)?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+}
+
+impl RecordedRequest {
+    fn new(request: &http::Request<Bytes>) -> Self {
+        let path = request
+            .uri()
+            .path_and_query()
+            .map_or_else(|| request.uri().path().to_owned(), |p| p.to_string());
+        let mut headers = headers(request.headers());
+        if let Some(value) = headers.get_mut(AUTHORIZATION.as_str()) {
+            *value = REDACTED.to_owned();
+        }
+
+        Self {
+            method: request.method().to_string(),
+            path,
+            headers,
+            body: RecordedBody::new(request.body()),
+        }
+    }
+
+    /// Same method, path and body. JSON bodies are compared as JSON, ignoring formatting.
+    fn matches(&self, other: &RecordedRequest) -> bool {
+        self.method == other.method && self.path == other.path && self.body == other.body
+    }
+}
+
+fn headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+/// Error of a [`ReplayTransport`] asked for a request the cassette has no recording of.
+#[derive(Debug)]
+pub struct NoRecordedInteraction {
+    pub method: String,
+    pub path: String,
+}
+
+impl fmt::Display for NoRecordedInteraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no recorded interaction for `{} {}`",
+            self.method, self.path
+        )
+    }
+}
+
+impl error::Error for NoRecordedInteraction {}
+
+/// [`HttpTransport`] sending requests through `T` and appending every request/response pair
+/// to a JSON [`Cassette`] file, rewritten after each interaction.
+///
+/// ```no_run
+/// # async fn record() -> Result<(), Box<dyn std::error::Error>> {
+/// use pedidosya_courier_rs::{PedidosYaClient, testing::RecordingTransport};
+///
+/// let transport = RecordingTransport::new(reqwest::Client::new(), "tests/cassettes/estimate.json");
+/// let client = PedidosYaClient::builder("sandbox-token").build_with_transport(transport)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl<T> RecordingTransport<T> {
+    /// Records into a new cassette at `path`, replacing any existing file on the first interaction.
+    pub fn new(inner: T, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Interactions recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Cassette> {
+        self.cassette.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T: HttpTransport> HttpTransport for RecordingTransport<T> {
+    type Body = Full<Bytes>;
+    type Error = Box<dyn error::Error + Send + Sync>;
+
+    async fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Self::Body>, Self::Error> {
+        let recorded_request = RecordedRequest::new(&request);
+
+        let response = self.inner.send(request).await.map_err(Into::into)?;
+        let (parts, body) = response.into_parts();
+        let body = body.collect().await.map_err(Into::into)?.to_bytes();
+
+        let interaction = Interaction {
+            request: recorded_request,
+            response: RecordedResponse {
+                status: parts.status.as_u16(),
+                headers: headers(&parts.headers),
+                body: RecordedBody::new(&body),
+            },
+        };
+        {
+            let mut cassette = self.lock();
+            cassette.interactions.push(interaction);
+            cassette.save(&self.path)?;
+        }
+
+        Ok(http::Response::from_parts(parts, Full::new(body)))
+    }
+}
+
+/// [`HttpTransport`] answering with the responses of a [`Cassette`] instead of sending requests.
+///
+/// Requests are matched on method, path and body. Each recorded interaction is served once,
+/// in recording order, so repeated calls replay successive responses. Replayed bodies are
+/// re-serialized, so the recorded `Content-Length` is dropped.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    interactions: Vec<Interaction>,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            used: Mutex::new(vec![false; cassette.interactions.len()]),
+            interactions: cassette.interactions,
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+}
+
+impl HttpTransport for ReplayTransport {
+    type Body = Full<Bytes>;
+    type Error = NoRecordedInteraction;
+
+    async fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Self::Body>, Self::Error> {
+        let request = RecordedRequest::new(&request);
+
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(index) = (0..self.interactions.len())
+            .find(|&i| !used[i] && self.interactions[i].request.matches(&request))
+        else {
+            return Err(NoRecordedInteraction {
+                method: request.method,
+                path: request.path,
+            });
+        };
+        used[index] = true;
+
+        let recorded = &self.interactions[index].response;
+        let mut response = http::Response::builder().status(recorded.status);
+        for (name, value) in &recorded.headers {
+            if name != CONTENT_LENGTH.as_str() && name != TRANSFER_ENCODING.as_str() {
+                response = response.header(name, value);
+            }
+        }
+        let body = recorded
+            .body
+            .as_ref()
+            .map(RecordedBody::to_bytes)
+            .unwrap_or_default();
+        Ok(response
+            .body(Full::new(body))
+            .expect("recorded responses have valid status and headers"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PedidosYaClient;
+    use crate::models::{
+        Error, Urls, WebhookConfiguration, WebhooksConfigModel, webhook_configuration,
+    };
+    use crate::testing::FakeCourierServer;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let server = FakeCourierServer::start().await.unwrap();
+        let dir = std::env::temp_dir().join(format!("pedidosya-cassette-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cancel.json");
+
+        let recorder = RecordingTransport::new(reqwest::Client::new(), &path);
+        let client = PedidosYaClient::builder("secret-token")
+            .base_path(server.base_url())
+            .build_with_transport(recorder)
+            .unwrap();
+        let recorded = client
+            .cancel_shipping("unknown", "duplicated")
+            .await
+            .unwrap_err();
+        assert!(recorded.is_not_found());
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret-token"));
+        assert!(content.contains(REDACTED));
+
+        let client = PedidosYaClient::builder("another-token")
+            .base_path("http://replay.test")
+            .build_with_transport(ReplayTransport::from_file(&path).unwrap())
+            .unwrap();
+        let replayed = client
+            .cancel_shipping("unknown", "duplicated")
+            .await
+            .unwrap_err();
+        assert_eq!(replayed.status(), recorded.status());
+        assert_eq!(replayed.api_message(), Some("Shipping not found"));
+        assert_eq!(replayed.api_message(), recorded.api_message());
+
+        let unmatched = client
+            .cancel_shipping("unknown", "another reason")
+            .await
+            .unwrap_err();
+        assert!(matches!(unmatched, Error::Transport(_)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_keys_are_redacted() {
+        let server = FakeCourierServer::start().await.unwrap();
+        let dir =
+            std::env::temp_dir().join(format!("pedidosya-cassette-keys-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("webhooks.json");
+
+        let client = PedidosYaClient::builder("secret-token")
+            .base_path(server.base_url())
+            .build_with_transport(RecordingTransport::new(reqwest::Client::new(), &path))
+            .unwrap();
+        let mut url = Urls::new("https://example.com/webhooks".to_owned());
+        url.authorization_key = Some("webhook-secret".to_owned());
+        let mut config = WebhooksConfigModel::new();
+        config.webhooks_configuration = Some(vec![WebhookConfiguration::new(
+            webhook_configuration::NotificationType::Webhook,
+            webhook_configuration::Topic::ShippingStatus,
+            vec![url],
+        )]);
+        client.set_webhooks_configuration(config).await.unwrap();
+        client.get_webhooks_configuration().await.unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("webhook-secret"), "{content}");
+
+        let replay = ReplayTransport::from_file(&path).unwrap();
+        let request = http::Request::get("http://replay.test/v3/webhooks-configuration")
+            .body(Bytes::new())
+            .unwrap();
+        let response = replay.send(request).await.unwrap();
+        assert!(response.headers().get(CONTENT_LENGTH).is_none());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let replayed: WebhooksConfigModel = serde_json::from_slice(&body).unwrap();
+        let urls = &replayed.webhooks_configuration.unwrap()[0].urls;
+        assert_eq!(urls[0].authorization_key.as_deref(), Some(REDACTED));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
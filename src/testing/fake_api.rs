@@ -0,0 +1,368 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use reqwest::StatusCode;
+
+use crate::PedidosYaApi;
+use crate::errors::{
+    CancelShippingError, ConfirmEstimateError, CreateShippingError, GetShippingsEstimatesError,
+    ShippingOderDetailsError, WebhookGetConfigurationError, WebhookSetConfigurationError,
+};
+use crate::models::{
+    ApiErrorCode, CancelCode, ConfirmEstimationShippingRequest, ConfirmShippingResponse,
+    DeliveryMode, DeliveryOffer, Error, EstimationShippingRequest, EstimationShippingResponse,
+    HttpErrorResponse, ResponseContent, ShippingResponse, ShippingRoutePricing, ShippingStatus,
+    WebhooksConfigModel,
+};
+
+/// In-memory implementation of [`PedidosYaApi`] for unit tests.
+///
+/// Estimates get a single express [`DeliveryOffer`]. Confirmed shippings start as
+/// [`ShippingStatus::Confirmed`] and only move when [`FakePedidosYaApi::set_status`] is called.
+/// Unknown ids and invalid transitions fail with the status of the real API, and estimates of
+/// a waypoint without street with its [`ApiErrorCode::AddressDataMissing`] code.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct FakePedidosYaApi {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+    estimates: HashMap<String, Estimate>,
+    shippings: HashMap<String, ShippingResponse>,
+    webhooks_configuration: WebhooksConfigModel,
+}
+
+#[derive(Debug)]
+struct Estimate {
+    response: EstimationShippingResponse,
+    shipping_id: Option<String>,
+}
+
+impl State {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn estimate<E: From<HttpErrorResponse>>(
+        &mut self,
+        request: EstimationShippingRequest,
+    ) -> Result<EstimationShippingResponse, Error<E>> {
+        if request
+            .waypoints
+            .iter()
+            .any(|waypoint| waypoint.address_street.trim().is_empty())
+        {
+            return Err(coded_api_error(
+                StatusCode::BAD_REQUEST,
+                ApiErrorCode::AddressDataMissing,
+                "Rider no encuentra el pickup/dropoff",
+            ));
+        }
+        let id = self.next_id();
+        let mut offer = DeliveryOffer::new();
+        offer.delivery_offer_id = Some(format!("offer-{id}"));
+        offer.delivery_mode = Some(DeliveryMode::Express);
+        offer.pricing = Some(Box::new(ShippingRoutePricing {
+            subtotal: Some(100.0),
+            taxes: Some(0.0),
+            total: Some(100.0),
+            currency: None,
+        }));
+
+        let response = EstimationShippingResponse {
+            estimate_id: Some(format!("estimate-{id}")),
+            reference_id: Some(request.reference_id),
+            is_test: request.is_test,
+            items: Some(request.items),
+            waypoints: Some(request.waypoints.into_iter().map(Into::into).collect()),
+            delivery_offers: Some(vec![offer]),
+            route: None,
+            notification_mail: request.notification_mail,
+        };
+        self.estimates.insert(
+            format!("estimate-{id}"),
+            Estimate {
+                response: response.clone(),
+                shipping_id: None,
+            },
+        );
+        Ok(response)
+    }
+
+    fn confirm<E: From<HttpErrorResponse>>(
+        &mut self,
+        estimate_id: &str,
+        request: ConfirmEstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<E>> {
+        let id = self.next_id();
+        let Some(estimate) = self.estimates.get_mut(estimate_id) else {
+            return Err(api_error(StatusCode::BAD_REQUEST, "Estimate not found"));
+        };
+        if estimate.shipping_id.is_some() {
+            return Err(api_error(
+                StatusCode::CONFLICT,
+                "Estimate already confirmed",
+            ));
+        }
+        let offers = estimate
+            .response
+            .delivery_offers
+            .clone()
+            .unwrap_or_default();
+        let offer = match &request.delivery_offer_id {
+            Some(offer_id) => offers
+                .into_iter()
+                .find(|o| o.delivery_offer_id.as_ref() == Some(offer_id)),
+            None => offers.into_iter().next(),
+        };
+        let Some(offer) = offer else {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "Delivery offer not found",
+            ));
+        };
+
+        let shipping_id = format!("shipping-{id}");
+        estimate.shipping_id = Some(shipping_id.clone());
+        let estimate = &estimate.response;
+        self.shippings.insert(
+            shipping_id.clone(),
+            ShippingResponse {
+                shipping_id: Some(shipping_id.clone()),
+                reference_id: estimate.reference_id.clone(),
+                is_test: estimate.is_test,
+                items: estimate.items.clone(),
+                waypoints: estimate.waypoints.clone(),
+                delivery_offers: Some(vec![offer]),
+                notification_mail: estimate.notification_mail.clone(),
+                status: Some(ShippingStatus::Confirmed),
+                ..ShippingResponse::new()
+            },
+        );
+
+        Ok(ConfirmShippingResponse {
+            estimate_id: Some(estimate_id.to_owned()),
+            shipping_id: Some(shipping_id),
+            confirmation_code: Some(format!("{id:06}")),
+            is_test: estimate.is_test,
+            reference_id: estimate.reference_id.clone(),
+            status: Some(ShippingStatus::Confirmed),
+            proof_of_delivery: Some(false),
+            items: estimate.items.clone(),
+            waypoints: estimate.waypoints.clone(),
+            notification_mail: estimate.notification_mail.clone(),
+            ..ConfirmShippingResponse::new()
+        })
+    }
+
+    fn shipping<E: From<HttpErrorResponse>>(
+        &mut self,
+        shipping_id: &str,
+    ) -> Result<&mut ShippingResponse, Error<E>> {
+        self.shippings
+            .get_mut(shipping_id)
+            .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Shipping not found"))
+    }
+}
+
+/// Builds the error the real API answers with, including the raw JSON body.
+fn api_error<E: From<HttpErrorResponse>>(status: StatusCode, message: &str) -> Error<E> {
+    error_response(status, None, message)
+}
+
+/// Like [`api_error`], for the errors the real API answers with an error code.
+fn coded_api_error<E: From<HttpErrorResponse>>(
+    status: StatusCode,
+    code: ApiErrorCode,
+    message: &str,
+) -> Error<E> {
+    error_response(status, Some(code), message)
+}
+
+fn error_response<E: From<HttpErrorResponse>>(
+    status: StatusCode,
+    code: Option<ApiErrorCode>,
+    message: &str,
+) -> Error<E> {
+    let response = HttpErrorResponse {
+        status: Some(i32::from(status.as_u16())),
+        message: Some(message.to_owned()),
+        code: code.map(String::from),
+    };
+    Error::ResponseError(ResponseContent {
+        status,
+        content: serde_json::to_string(&response).unwrap_or_default(),
+        entity: Some(E::from(response)),
+    })
+}
+
+impl FakePedidosYaApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Current state of a shipping, as returned by the details endpoint.
+    pub fn shipping(&self, shipping_id: &str) -> Option<ShippingResponse> {
+        self.state().shippings.get(shipping_id).cloned()
+    }
+
+    /// Moves a shipping to `status`, as the courier would while delivering it.
+    /// Returns `false` if the shipping does not exist.
+    pub fn set_status(&self, shipping_id: &str, status: ShippingStatus) -> bool {
+        match self.state().shippings.get_mut(shipping_id) {
+            Some(shipping) => {
+                shipping.status = Some(status);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl PedidosYaApi for FakePedidosYaApi {
+    async fn shipping_estimate_shipping_order(
+        &self,
+        estimation_shipping_request: EstimationShippingRequest,
+    ) -> Result<EstimationShippingResponse, Error<GetShippingsEstimatesError>> {
+        self.state().estimate(estimation_shipping_request)
+    }
+
+    async fn shipping_confirm_estimate_order(
+        &self,
+        estimate_id: String,
+        confirm_estimate_request: ConfirmEstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<ConfirmEstimateError>> {
+        self.state().confirm(&estimate_id, confirm_estimate_request)
+    }
+
+    async fn create_shipping(
+        &self,
+        shipping_request: EstimationShippingRequest,
+    ) -> Result<ConfirmShippingResponse, Error<CreateShippingError>> {
+        let mut state = self.state();
+        let estimate = state.estimate(shipping_request)?;
+        let estimate_id = estimate.estimate_id.unwrap_or_default();
+        state.confirm(&estimate_id, ConfirmEstimationShippingRequest::new())
+    }
+
+    async fn shippings_shipping_oder_details_get(
+        &self,
+        shipping_id: String,
+    ) -> Result<ShippingResponse, Error<ShippingOderDetailsError>> {
+        self.state().shipping(&shipping_id).cloned()
+    }
+
+    async fn cancel_shipping(
+        &self,
+        shipping_id: String,
+        reason: String,
+    ) -> Result<ShippingResponse, Error<CancelShippingError>> {
+        let mut state = self.state();
+        let shipping = state.shipping(&shipping_id)?;
+        if matches!(
+            shipping.status,
+            Some(ShippingStatus::Completed | ShippingStatus::Cancelled | ShippingStatus::Rejected)
+        ) {
+            return Err(api_error(
+                StatusCode::CONFLICT,
+                "Shipping can no longer be cancelled",
+            ));
+        }
+
+        shipping.status = Some(ShippingStatus::Cancelled);
+        shipping.cancel_code = Some(CancelCode::UserCancelled);
+        shipping.cancel_reason = Some(reason);
+        Ok(shipping.clone())
+    }
+
+    async fn get_webhooks_configuration(
+        &self,
+    ) -> Result<WebhooksConfigModel, Error<WebhookGetConfigurationError>> {
+        Ok(self.state().webhooks_configuration.clone())
+    }
+
+    async fn set_webhooks_configuration(
+        &self,
+        webhook_config_request: WebhooksConfigModel,
+    ) -> Result<WebhooksConfigModel, Error<WebhookSetConfigurationError>> {
+        self.state().webhooks_configuration = webhook_config_request.clone();
+        Ok(webhook_config_request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{ShippingItemRequest, WayPointModel, WayPointModelType};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fake_shipping_lifecycle() {
+        let api = FakePedidosYaApi::new();
+        let item = ShippingItemRequest::new(1500.0, "Pizza".to_owned(), 1, 10.0, 1.0);
+        let request = EstimationShippingRequest::new("order-1".to_owned(), vec![item], vec![]);
+
+        let estimate = api.shipping_estimate_shipping_order(request).await.unwrap();
+        let estimate_id = estimate.estimate_id.unwrap();
+        let confirmed = api
+            .shipping_confirm_estimate_order(
+                estimate_id.clone(),
+                ConfirmEstimationShippingRequest::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(confirmed.status, Some(ShippingStatus::Confirmed));
+        assert_eq!(confirmed.reference_id.as_deref(), Some("order-1"));
+
+        let again = api
+            .shipping_confirm_estimate_order(estimate_id, ConfirmEstimationShippingRequest::new())
+            .await
+            .unwrap_err();
+        assert!(again.is_conflict());
+        assert_eq!(again.api_message(), Some("Estimate already confirmed"));
+
+        let shipping_id = confirmed.shipping_id.unwrap();
+        assert!(api.set_status(&shipping_id, ShippingStatus::PickedUp));
+        let cancelled = api
+            .cancel_shipping(shipping_id.clone(), "duplicated".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(cancelled.cancel_code, Some(CancelCode::UserCancelled));
+
+        let missing = api
+            .shippings_shipping_oder_details_get("unknown".to_owned())
+            .await
+            .unwrap_err();
+        assert!(missing.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_error_code() {
+        let api = FakePedidosYaApi::new();
+        let item = ShippingItemRequest::new(1500.0, "Pizza".to_owned(), 1, 10.0, 1.0);
+        let waypoint = WayPointModel::new(
+            " ".to_owned(),
+            "+59899999999".to_owned(),
+            "Juan".to_owned(),
+            "Montevideo".to_owned(),
+            WayPointModelType::DropOff,
+        );
+        let request =
+            EstimationShippingRequest::new("order-1".to_owned(), vec![item], vec![waypoint]);
+
+        let error = api.create_shipping(request).await.unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(error.api_code(), Some("ADDRESS_DATA_MISSING"));
+        assert_eq!(error.error_code(), Some(ApiErrorCode::AddressDataMissing));
+    }
+}
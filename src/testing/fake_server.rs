@@ -0,0 +1,796 @@
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+};
+use http_body::{Frame, SizeHint};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::sync::{mpsc, oneshot};
+
+use super::FakePedidosYaApi;
+use crate::models::{
+    CallbackRequest, CallbackRequestData, CallbackShippingStatus, CallbackTopic,
+    CancelShippingRequest, ConfirmEstimationShippingRequest, ConfirmShippingResponse,
+    CoverageResponse, Error, EstimationShippingRequest, HttpErrorResponse, ShippingStatus,
+    WebhooksConfigModel,
+};
+use crate::{PedidosYaApi, PedidosYaClient};
+
+/// Unix time the fake clock starts at: 2024-01-01T12:00:00Z.
+const CLOCK_START: u64 = 1_704_110_400;
+
+/// How long a webhook may take to answer a callback before it is recorded as failed.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// In-process fake of the courier API listening on `127.0.0.1`, for end to end tests.
+///
+/// Estimates, confirmations, cancellations and the webhook configuration behave as in
+/// [`FakePedidosYaApi`]. Confirmed shippings then move through the statuses of the
+/// [timeline](FakeCourierServer::start_with_timeline) as the fake clock is moved with
+/// [`FakeCourierServer::advance`], and every status change is POSTed as a [`CallbackRequest`]
+/// to the configured webhook URLs, with their `authorizationKey` as `Authorization` header.
+///
+/// Callbacks are POSTed one at a time, in order, by a background task. Those caused by an API
+/// call, like the `CONFIRMED` callback of a confirmation, are only sent once the API response
+/// was, as the real API does. A webhook answering slower than 5 seconds is recorded as failed.
+///
+/// The server shuts down when dropped.
+#[derive(Debug)]
+pub struct FakeCourierServer {
+    addr: SocketAddr,
+    state: Arc<ServerState>,
+    server: tokio::task::JoinHandle<()>,
+    outbox: tokio::task::JoinHandle<()>,
+}
+
+#[derive(Debug)]
+struct ServerState {
+    api: FakePedidosYaApi,
+    http: reqwest::Client,
+    outbox: mpsc::UnboundedSender<Outgoing>,
+    timeline: Vec<(Duration, ShippingStatus)>,
+    clock: Mutex<Clock>,
+    deliveries: Mutex<Vec<CallbackDelivery>>,
+}
+
+#[derive(Debug, Default)]
+struct Clock {
+    elapsed: Duration,
+    shipments: Vec<Shipment>,
+}
+
+/// A confirmed shipping following the timeline.
+#[derive(Debug)]
+struct Shipment {
+    shipping_id: String,
+    reference_id: Option<String>,
+    confirmed_at: Duration,
+    next_step: usize,
+}
+
+/// A callback waiting to be delivered.
+#[derive(Debug)]
+struct Outgoing {
+    callback: CallbackRequest,
+    /// Resolves once the API response that caused the callback was sent.
+    after: Option<oneshot::Receiver<()>>,
+    delivered: oneshot::Sender<()>,
+}
+
+/// A callback POSTed by the fake server, with the status code the webhook answered with.
+#[derive(Debug, Clone)]
+pub struct CallbackDelivery {
+    pub url: String,
+    pub callback: CallbackRequest,
+    /// `None` if the request failed before getting a response.
+    pub status: Option<u16>,
+}
+
+impl FakeCourierServer {
+    /// Delay after the confirmation at which a shipping reaches each status, by default.
+    pub const DEFAULT_TIMELINE: [(Duration, ShippingStatus); 5] = [
+        (Duration::from_secs(2 * 60), ShippingStatus::InProgress),
+        (Duration::from_secs(5 * 60), ShippingStatus::NearPickup),
+        (Duration::from_secs(10 * 60), ShippingStatus::PickedUp),
+        (Duration::from_secs(20 * 60), ShippingStatus::NearDropoff),
+        (Duration::from_secs(25 * 60), ShippingStatus::Completed),
+    ];
+
+    /// Starts a server following [`Self::DEFAULT_TIMELINE`].
+    pub async fn start() -> std::io::Result<Self> {
+        Self::start_with_timeline(Self::DEFAULT_TIMELINE.to_vec()).await
+    }
+
+    /// Starts a server where confirmed shippings reach each status after the given delay
+    /// since their confirmation. Delays must be increasing.
+    pub async fn start_with_timeline(
+        timeline: Vec<(Duration, ShippingStatus)>,
+    ) -> std::io::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .map_err(std::io::Error::other)?;
+        let (outbox, outgoing) = mpsc::unbounded_channel();
+        let state = Arc::new(ServerState {
+            api: FakePedidosYaApi::new(),
+            http,
+            outbox,
+            timeline,
+            clock: Mutex::new(Clock::default()),
+            deliveries: Mutex::new(Vec::new()),
+        });
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let router = router(state.clone());
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        let outbox = tokio::spawn(deliver_outbox(state.clone(), outgoing));
+
+        Ok(Self {
+            addr,
+            state,
+            server,
+            outbox,
+        })
+    }
+
+    /// Base URL to point [`crate::PedidosYaClientBuilder::base_path`] at.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A client for this server. Any non-empty auth token is accepted.
+    pub fn client(&self) -> PedidosYaClient {
+        PedidosYaClient::builder("fake-token")
+            .base_path(self.base_url())
+            .build()
+            .expect("the fake server url is valid")
+    }
+
+    /// The state behind the server, to inspect or tweak shippings directly.
+    pub fn api(&self) -> &FakePedidosYaApi {
+        &self.state.api
+    }
+
+    /// Time elapsed on the fake clock since the server started.
+    pub fn elapsed(&self) -> Duration {
+        self.state.clock().elapsed
+    }
+
+    /// Moves the fake clock forward, applying every status change that became due and
+    /// delivering its callbacks before returning.
+    pub async fn advance(&self, by: Duration) {
+        let due = {
+            let mut clock = self.state.clock();
+            clock.elapsed += by;
+            let now = clock.elapsed;
+
+            let mut due = Vec::new();
+            for shipment in &mut clock.shipments {
+                while let Some((delay, status)) = self.state.timeline.get(shipment.next_step) {
+                    let at = shipment.confirmed_at + *delay;
+                    if at > now {
+                        break;
+                    }
+                    shipment.next_step += 1;
+                    due.push((
+                        at,
+                        shipment.shipping_id.clone(),
+                        shipment.reference_id.clone(),
+                        *status,
+                    ));
+                }
+            }
+            clock
+                .shipments
+                .retain(|shipment| shipment.next_step < self.state.timeline.len());
+            due.sort_by_key(|(at, ..)| *at);
+            due
+        };
+
+        let mut delivered = None;
+        for (at, shipping_id, reference_id, status) in due {
+            // Shippings cancelled in the meantime stay cancelled.
+            let current = self.state.api.shipping(&shipping_id).and_then(|s| s.status);
+            if current == Some(ShippingStatus::Cancelled) {
+                continue;
+            }
+            self.state.api.set_status(&shipping_id, status);
+            let callback =
+                self.state
+                    .callback(at, shipping_id, reference_id, callback_data(status));
+            delivered = Some(self.state.enqueue(callback, None));
+        }
+        if let Some(delivered) = delivered {
+            let _ = delivered.await;
+        }
+    }
+
+    /// Every callback sent so far, in order.
+    pub fn deliveries(&self) -> Vec<CallbackDelivery> {
+        self.state.deliveries().clone()
+    }
+}
+
+impl Drop for FakeCourierServer {
+    fn drop(&mut self) {
+        self.server.abort();
+        self.outbox.abort();
+    }
+}
+
+impl ServerState {
+    fn clock(&self) -> MutexGuard<'_, Clock> {
+        self.clock.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn deliveries(&self) -> MutexGuard<'_, Vec<CallbackDelivery>> {
+        self.deliveries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn callback(
+        &self,
+        at: Duration,
+        shipping_id: String,
+        reference_id: Option<String>,
+        data: CallbackRequestData,
+    ) -> CallbackRequest {
+        let generated = timestamp(at);
+        CallbackRequest {
+            topic: Some(CallbackTopic::ShippingStatus),
+            id: Some(shipping_id),
+            reference_id,
+            transmitted: Some(generated.clone()),
+            generated: Some(generated),
+            data: Some(Box::new(data)),
+        }
+    }
+
+    /// Queues `callback`, to be delivered after every callback queued before it and, if
+    /// given, once `after` resolves. The returned receiver resolves once it was delivered.
+    fn enqueue(
+        &self,
+        callback: CallbackRequest,
+        after: Option<oneshot::Receiver<()>>,
+    ) -> oneshot::Receiver<()> {
+        let (delivered, done) = oneshot::channel();
+        let _ = self.outbox.send(Outgoing {
+            callback,
+            after,
+            delivered,
+        });
+        done
+    }
+
+    /// Starts the timeline of a newly confirmed shipping and queues its `CONFIRMED` callback,
+    /// sent once `after` resolves.
+    fn confirmed(&self, confirmation: &ConfirmShippingResponse, after: oneshot::Receiver<()>) {
+        let Some(shipping_id) = confirmation.shipping_id.clone() else {
+            return;
+        };
+        let now = {
+            let mut clock = self.clock();
+            let now = clock.elapsed;
+            clock.shipments.push(Shipment {
+                shipping_id: shipping_id.clone(),
+                reference_id: confirmation.reference_id.clone(),
+                confirmed_at: now,
+                next_step: 0,
+            });
+            now
+        };
+        let callback = self.callback(
+            now,
+            shipping_id,
+            confirmation.reference_id.clone(),
+            callback_data(ShippingStatus::Confirmed),
+        );
+        self.enqueue(callback, Some(after));
+    }
+
+    async fn deliver(&self, callback: CallbackRequest) {
+        let configuration = self
+            .api
+            .get_webhooks_configuration()
+            .await
+            .unwrap_or_default();
+        let urls = configuration
+            .webhooks_configuration
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|webhook| webhook.urls);
+
+        for url in urls {
+            let mut request = self
+                .http
+                .post(&url.url)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&callback).unwrap_or_default());
+            if let Some(key) = &url.authorization_key {
+                request = request.header(header::AUTHORIZATION, key);
+            }
+            let status = request.send().await.ok().map(|r| r.status().as_u16());
+            self.deliveries().push(CallbackDelivery {
+                url: url.url,
+                callback: callback.clone(),
+                status,
+            });
+        }
+    }
+}
+
+async fn deliver_outbox(state: Arc<ServerState>, mut outbox: mpsc::UnboundedReceiver<Outgoing>) {
+    while let Some(outgoing) = outbox.recv().await {
+        if let Some(after) = outgoing.after {
+            let _ = after.await;
+        }
+        state.deliver(outgoing.callback).await;
+        let _ = outgoing.delivered.send(());
+    }
+}
+
+/// Response body resolving its paired receiver once dropped, which hyper does after writing it.
+struct NotifyWhenSent {
+    body: Body,
+    _sent: oneshot::Sender<()>,
+}
+
+impl http_body::Body for NotifyWhenSent {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, axum::Error>>> {
+        Pin::new(&mut self.body).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+/// Resolves the receiver of `sent` once `reply` was sent.
+fn notify_when_sent(reply: Reply, sent: oneshot::Sender<()>) -> Reply {
+    reply.map(|response| response.map(|body| Body::new(NotifyWhenSent { body, _sent: sent })))
+}
+
+fn callback_data(status: ShippingStatus) -> CallbackRequestData {
+    let mut data = CallbackRequestData::new();
+    data.status = match status {
+        ShippingStatus::Confirmed => Some(CallbackShippingStatus::Confirmed),
+        ShippingStatus::InProgress => Some(CallbackShippingStatus::InProgress),
+        ShippingStatus::NearPickup => Some(CallbackShippingStatus::NearPickup),
+        ShippingStatus::PickedUp => Some(CallbackShippingStatus::PickedUp),
+        ShippingStatus::NearDropoff => Some(CallbackShippingStatus::NearDropoff),
+        ShippingStatus::Completed => Some(CallbackShippingStatus::Completed),
+        ShippingStatus::Cancelled => Some(CallbackShippingStatus::Cancelled),
+        ShippingStatus::Rejected => None,
+    };
+    data
+}
+
+fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/v3/estimates/coverage", post(check_coverage))
+        .route("/v3/shippings/estimates", post(estimate_shipping))
+        .route(
+            "/v3/shippings/estimates/{id}/confirm",
+            post(confirm_estimate),
+        )
+        .route("/v3/shippings", post(create_shipping))
+        .route("/v3/shippings/{id}", get(get_shipping))
+        .route("/v3/shippings/{id}/cancel", put(cancel_shipping))
+        .route(
+            "/v3/webhooks-configuration",
+            get(get_webhooks_configuration).put(set_webhooks_configuration),
+        )
+        .with_state(state)
+}
+
+type Reply = Result<Response, Failure>;
+
+/// Error answer of the fake API: a status with an [`HttpErrorResponse`] JSON body.
+struct Failure {
+    status: StatusCode,
+    body: String,
+}
+
+impl IntoResponse for Failure {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            [(header::CONTENT_TYPE, "application/json")],
+            self.body,
+        )
+            .into_response()
+    }
+}
+
+async fn check_coverage(headers: HeaderMap) -> Reply {
+    authorize(&headers)?;
+    Ok(json(StatusCode::OK, &CoverageResponse::new(true)))
+}
+
+async fn estimate_shipping(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Reply {
+    authorize(&headers)?;
+    let request: EstimationShippingRequest = parse(&body)?;
+    reply(state.api.shipping_estimate_shipping_order(request).await)
+}
+
+async fn confirm_estimate(
+    State(state): State<Arc<ServerState>>,
+    Path(estimate_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Reply {
+    authorize(&headers)?;
+    let request: ConfirmEstimationShippingRequest = parse(&body)?;
+    let result = state
+        .api
+        .shipping_confirm_estimate_order(estimate_id, request)
+        .await;
+    let (sent, after) = oneshot::channel();
+    if let Ok(confirmation) = &result {
+        state.confirmed(confirmation, after);
+    }
+    notify_when_sent(reply(result), sent)
+}
+
+async fn create_shipping(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Reply {
+    authorize(&headers)?;
+    let request: EstimationShippingRequest = parse(&body)?;
+    let result = state.api.create_shipping(request).await;
+    let (sent, after) = oneshot::channel();
+    if let Ok(confirmation) = &result {
+        state.confirmed(confirmation, after);
+    }
+    notify_when_sent(reply(result), sent)
+}
+
+async fn get_shipping(
+    State(state): State<Arc<ServerState>>,
+    Path(shipping_id): Path<String>,
+    headers: HeaderMap,
+) -> Reply {
+    authorize(&headers)?;
+    reply(
+        state
+            .api
+            .shippings_shipping_oder_details_get(shipping_id)
+            .await,
+    )
+}
+
+async fn cancel_shipping(
+    State(state): State<Arc<ServerState>>,
+    Path(shipping_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Reply {
+    authorize(&headers)?;
+    let request: CancelShippingRequest = parse(&body)?;
+    let result = state
+        .api
+        .cancel_shipping(shipping_id, request.reason_text)
+        .await;
+    let (sent, after) = oneshot::channel();
+    if let Ok(shipping) = &result {
+        let now = state.clock().elapsed;
+        let mut data = callback_data(ShippingStatus::Cancelled);
+        data.cancel_code = shipping.cancel_code.clone();
+        data.cancel_reason = shipping.cancel_reason.clone();
+        let callback = state.callback(
+            now,
+            shipping.shipping_id.clone().unwrap_or_default(),
+            shipping.reference_id.clone(),
+            data,
+        );
+        state.enqueue(callback, Some(after));
+    }
+    notify_when_sent(reply(result), sent)
+}
+
+async fn get_webhooks_configuration(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+) -> Reply {
+    authorize(&headers)?;
+    reply(state.api.get_webhooks_configuration().await)
+}
+
+async fn set_webhooks_configuration(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Reply {
+    authorize(&headers)?;
+    let request: WebhooksConfigModel = parse(&body)?;
+    reply(state.api.set_webhooks_configuration(request).await)
+}
+
+fn authorize(headers: &HeaderMap) -> Result<(), Failure> {
+    match headers.get(header::AUTHORIZATION) {
+        Some(token) if !token.is_empty() => Ok(()),
+        _ => Err(error(StatusCode::FORBIDDEN, "Missing authorization token")),
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, Failure> {
+    serde_json::from_slice(body).map_err(|e| error(StatusCode::BAD_REQUEST, &e.to_string()))
+}
+
+fn reply<T: Serialize, E>(result: Result<T, Error<E>>) -> Reply {
+    match result {
+        Ok(body) => Ok(json(StatusCode::OK, &body)),
+        Err(Error::ResponseError(content)) => Err(Failure {
+            status: content.status,
+            body: content.content,
+        }),
+        Err(e) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())),
+    }
+}
+
+fn json<T: Serialize>(status: StatusCode, body: &T) -> Response {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    (status, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+fn error(status: StatusCode, message: &str) -> Failure {
+    let body = HttpErrorResponse {
+        status: Some(i32::from(status.as_u16())),
+        message: Some(message.to_owned()),
+        code: None,
+    };
+    Failure {
+        status,
+        body: serde_json::to_string(&body).unwrap_or_default(),
+    }
+}
+
+/// Formats the fake clock as an ISO 8601 UTC date time, e.g. `2024-01-01T12:00:00Z`.
+fn timestamp(elapsed: Duration) -> String {
+    let secs = CLOCK_START + elapsed.as_secs();
+    let (days, time) = (secs / 86_400, secs % 86_400);
+
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        ApiErrorCode, ShippingItemRequest, Urls, WayPointModel, WayPointModelType,
+        WebhookConfiguration, webhook_configuration,
+    };
+
+    use super::*;
+
+    /// Serves `webhook` on `127.0.0.1` and returns its `/callbacks` URL.
+    async fn serve_webhook(webhook: Router) -> String {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let webhook_url = format!("http://{}/callbacks", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, webhook).await });
+        webhook_url
+    }
+
+    fn webhook_config(webhook_url: String) -> WebhooksConfigModel {
+        let mut url = Urls::new(webhook_url);
+        url.authorization_key = Some("secret".to_owned());
+        let mut config = WebhooksConfigModel::new();
+        config.webhooks_configuration = Some(vec![WebhookConfiguration::new(
+            webhook_configuration::NotificationType::Webhook,
+            webhook_configuration::Topic::ShippingStatus,
+            vec![url],
+        )]);
+        config
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(Duration::ZERO), "2024-01-01T12:00:00Z");
+        assert_eq!(
+            timestamp(Duration::from_secs(60 * 24 * 3_600 + 90)),
+            "2024-03-01T12:01:30Z"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shipping_lifecycle_with_webhooks() {
+        let receiver = Arc::new(Mutex::new(Vec::new()));
+        let received = receiver.clone();
+        let webhook = Router::new().route(
+            "/callbacks",
+            post(move |headers: HeaderMap, body: Bytes| {
+                let received = received.clone();
+                async move {
+                    let callback: CallbackRequest = serde_json::from_slice(&body).unwrap();
+                    let key = headers[header::AUTHORIZATION].to_str().unwrap().to_owned();
+                    received.lock().unwrap().push((key, callback));
+                    StatusCode::OK
+                }
+            }),
+        );
+        let webhook_url = serve_webhook(webhook).await;
+
+        let server = FakeCourierServer::start().await.unwrap();
+        let client = server.client();
+        client
+            .set_webhooks_configuration(webhook_config(webhook_url))
+            .await
+            .unwrap();
+
+        let item = ShippingItemRequest::new(1500.0, "Pizza".to_owned(), 1, 10.0, 1.0);
+        let request = EstimationShippingRequest::new("order-1".to_owned(), vec![item], vec![]);
+        let estimate = client
+            .shipping_estimate_shipping_order(request)
+            .await
+            .unwrap();
+        let offer = estimate.delivery_offers.unwrap().remove(0);
+        let mut confirm = ConfirmEstimationShippingRequest::new();
+        confirm.delivery_offer_id = offer.delivery_offer_id;
+        let confirmation = client
+            .shipping_confirm_estimate_order(estimate.estimate_id.unwrap(), confirm)
+            .await
+            .unwrap();
+        let shipping_id = confirmation.shipping_id.unwrap();
+
+        server.advance(Duration::from_secs(12 * 60)).await;
+        let shipping = client
+            .shippings_shipping_oder_details_get(shipping_id.clone())
+            .await
+            .unwrap();
+        assert_eq!(shipping.status, Some(ShippingStatus::PickedUp));
+
+        server.advance(Duration::from_secs(60 * 60)).await;
+
+        let received = receiver.lock().unwrap();
+        let statuses: Vec<_> = received
+            .iter()
+            .map(|(_, callback)| callback.data.as_ref().unwrap().status.unwrap())
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                CallbackShippingStatus::Confirmed,
+                CallbackShippingStatus::InProgress,
+                CallbackShippingStatus::NearPickup,
+                CallbackShippingStatus::PickedUp,
+                CallbackShippingStatus::NearDropoff,
+                CallbackShippingStatus::Completed,
+            ]
+        );
+        assert!(received.iter().all(|(key, callback)| {
+            key == "secret"
+                && callback.id.as_deref() == Some(shipping_id.as_str())
+                && callback.reference_id.as_deref() == Some("order-1")
+        }));
+        assert_eq!(
+            received[1].1.generated.as_deref(),
+            Some("2024-01-01T12:02:00Z")
+        );
+        assert_eq!(server.deliveries().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_callbacks_do_not_block_the_api() {
+        let release = Arc::new(tokio::sync::Notify::new());
+        let (received, mut callbacks) = mpsc::unbounded_channel();
+        let webhook = Router::new().route(
+            "/callbacks",
+            post({
+                let release = release.clone();
+                move |body: Bytes| async move {
+                    release.notified().await;
+                    let callback: CallbackRequest = serde_json::from_slice(&body).unwrap();
+                    let _ = received.send(callback);
+                    StatusCode::OK
+                }
+            }),
+        );
+        let server = FakeCourierServer::start().await.unwrap();
+        let client = server.client();
+        client
+            .set_webhooks_configuration(webhook_config(serve_webhook(webhook).await))
+            .await
+            .unwrap();
+
+        let item = ShippingItemRequest::new(1500.0, "Pizza".to_owned(), 1, 10.0, 1.0);
+        let request = EstimationShippingRequest::new("order-1".to_owned(), vec![item], vec![]);
+        let confirmation =
+            tokio::time::timeout(Duration::from_secs(1), client.create_shipping(request))
+                .await
+                .expect("the API answers while the webhook hangs")
+                .unwrap();
+
+        release.notify_one();
+        let callback = callbacks.recv().await.unwrap();
+        assert_eq!(callback.id, confirmation.shipping_id);
+        assert_eq!(
+            callback.data.unwrap().status,
+            Some(CallbackShippingStatus::Confirmed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_shipping() {
+        let server = FakeCourierServer::start().await.unwrap();
+
+        let error = server
+            .client()
+            .shippings_shipping_oder_details_get("unknown")
+            .await
+            .unwrap_err();
+
+        assert!(error.is_not_found());
+        assert_eq!(error.api_message(), Some("Shipping not found"));
+    }
+
+    #[tokio::test]
+    async fn test_error_code() {
+        let server = FakeCourierServer::start().await.unwrap();
+        let item = ShippingItemRequest::new(1500.0, "Pizza".to_owned(), 1, 10.0, 1.0);
+        let waypoint = WayPointModel::new(
+            String::new(),
+            "+59899999999".to_owned(),
+            "Juan".to_owned(),
+            "Montevideo".to_owned(),
+            WayPointModelType::DropOff,
+        );
+        let request =
+            EstimationShippingRequest::new("order-1".to_owned(), vec![item], vec![waypoint]);
+
+        let error = server
+            .client()
+            .shipping_estimate_shipping_order(request)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(error.api_code(), Some("ADDRESS_DATA_MISSING"));
+        assert_eq!(error.error_code(), Some(ApiErrorCode::AddressDataMissing));
+    }
+}
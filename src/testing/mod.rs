@@ -0,0 +1,13 @@
+//! Test doubles for code built on top of this crate: an in-memory [`FakePedidosYaApi`],
+//! an in-process [`FakeCourierServer`] and record/replay cassettes. Requires the `testing` feature.
+
+mod cassette;
+mod fake_api;
+mod fake_server;
+
+pub use cassette::{
+    Cassette, Interaction, NoRecordedInteraction, RecordedBody, RecordedRequest, RecordedResponse,
+    RecordingTransport, ReplayTransport,
+};
+pub use fake_api::FakePedidosYaApi;
+pub use fake_server::{CallbackDelivery, FakeCourierServer};
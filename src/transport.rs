@@ -0,0 +1,32 @@
+use std::{error::Error, future::Future};
+
+use bytes::Bytes;
+
+/// HTTP backend used by [`crate::PedidosYaClient`] to send requests.
+///
+/// Requests come fully prepared, with the absolute URL, the `Authorization` header and
+/// the JSON body already set. Implement it to plug in another HTTP client (for example a
+/// hyper client configured for mTLS) or to answer with canned responses in tests.
+/// `reqwest::Client` is the default implementation.
+pub trait HttpTransport: Send + Sync {
+    type Body: http_body::Body<Data = Bytes, Error: Into<Box<dyn Error + Send + Sync>>> + Send;
+    type Error: Into<Box<dyn Error + Send + Sync>>;
+
+    fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> impl Future<Output = Result<http::Response<Self::Body>, Self::Error>> + Send;
+}
+
+impl HttpTransport for reqwest::Client {
+    type Body = reqwest::Body;
+    type Error = reqwest::Error;
+
+    async fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Self::Body>, Self::Error> {
+        let response = self.execute(reqwest::Request::try_from(request)?).await?;
+        Ok(response.into())
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    error::Error,
+    future::{self, Future},
+    sync::{Mutex, MutexGuard},
+    task::{Poll, Waker},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{CallbackRequest, CallbackShippingStatus, CallbackTopic};
+use crate::webhook::WebhookHandler;
+
+/// The (`id`, status, `generated`) triple identifying a shipping status callback.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ShippingState {
+    pub shipping_id: String,
+    pub status: CallbackShippingStatus,
+    /// ISO 8601 timestamp of the status change, as sent in `CallbackRequest.generated`.
+    pub generated: String,
+}
+
+/// How a callback relates to the last state recorded for its shipping.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// First callback of the shipping, or one moving it forward.
+    New,
+    /// Same status and `generated` timestamp as the recorded state.
+    Duplicate,
+    /// Generated before the recorded state, behind it in the status ordering, or after
+    /// the shipping was completed or cancelled.
+    OutOfOrder,
+}
+
+/// Position of `status` in the lifecycle of a shipping. Completed and cancelled are both final.
+fn progress(status: CallbackShippingStatus) -> u8 {
+    match status {
+        CallbackShippingStatus::Confirmed => 0,
+        CallbackShippingStatus::InProgress => 1,
+        CallbackShippingStatus::NearPickup => 2,
+        CallbackShippingStatus::PickedUp => 3,
+        CallbackShippingStatus::NearDropoff => 4,
+        CallbackShippingStatus::Completed | CallbackShippingStatus::Cancelled => 5,
+    }
+}
+
+/// Compares ISO 8601 UTC timestamps, whole seconds first so `12:00:00Z` sorts before
+/// `12:00:00.5Z`.
+fn compare_timestamps(a: &str, b: &str) -> Ordering {
+    fn split(s: &str) -> (&str, &str) {
+        s.split_at_checked(19).unwrap_or((s, ""))
+    }
+    let (a_seconds, a_rest) = split(a);
+    let (b_seconds, b_rest) = split(b);
+    a_seconds.cmp(b_seconds).then_with(|| {
+        a_rest
+            .trim_end_matches('Z')
+            .cmp(b_rest.trim_end_matches('Z'))
+    })
+}
+
+impl ShippingState {
+    /// The state carried by a shipping status callback, `None` for other topics or when
+    /// the id, status or `generated` timestamp is missing.
+    pub fn from_callback(callback: &CallbackRequest) -> Option<Self> {
+        if !matches!(callback.topic, None | Some(CallbackTopic::ShippingStatus)) {
+            return None;
+        }
+        Some(Self {
+            shipping_id: callback.id.clone()?,
+            status: callback.data.as_ref()?.status?,
+            generated: callback.generated.clone()?,
+        })
+    }
+
+    /// Judges `self` against the `last` state recorded for the same shipping.
+    pub fn verdict(&self, last: Option<&ShippingState>) -> Verdict {
+        let Some(last) = last else {
+            return Verdict::New;
+        };
+        if self.status == last.status && self.generated == last.generated {
+            return Verdict::Duplicate;
+        }
+        let last_progress = progress(last.status);
+        if last_progress == progress(CallbackShippingStatus::Completed)
+            || progress(self.status) < last_progress
+            || compare_timestamps(&self.generated, &last.generated) == Ordering::Less
+        {
+            return Verdict::OutOfOrder;
+        }
+        Verdict::New
+    }
+}
+
+/// Where [`Deduplicate`] keeps the last accepted state of every shipping.
+///
+/// Implementations must make [`WebhookStore::record`] keep the most advanced state when
+/// called concurrently, which [`ShippingState::verdict`] decides.
+pub trait WebhookStore: Send + Sync + 'static {
+    type Error: Into<Box<dyn Error + Send + Sync>>;
+
+    fn last_state(
+        &self,
+        shipping_id: &str,
+    ) -> impl Future<Output = Result<Option<ShippingState>, Self::Error>> + Send;
+
+    /// Records `state` as the last state of its shipping, unless the recorded one is
+    /// already ahead of it.
+    fn record(&self, state: ShippingState) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// Handler dropping duplicated and out of order callbacks before they reach `H`.
+///
+/// A callback is recorded in the store only after `H` handled it successfully, so a
+/// failed callback is processed again when PedidosYa retries it. Callbacks of the same
+/// shipping are handled one at a time, from reading the last state to recording the new
+/// one, so concurrent deliveries of the same callback reach `H` once. Callbacks without a
+/// [`ShippingState`] are passed through.
+///
+/// The lock is held by this `Deduplicate` only: processes sharing a store still need to
+/// route the callbacks of a shipping to the same instance.
+#[derive(Debug)]
+pub struct Deduplicate<H, S> {
+    inner: H,
+    store: S,
+    locks: ShippingLocks,
+}
+
+impl<H: WebhookHandler, S: WebhookStore> Deduplicate<H, S> {
+    pub fn new(inner: H, store: S) -> Self {
+        Self {
+            inner,
+            store,
+            locks: ShippingLocks::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<H: WebhookHandler, S: WebhookStore> WebhookHandler for Deduplicate<H, S> {
+    async fn handle(&self, callback: CallbackRequest) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(state) = ShippingState::from_callback(&callback) else {
+            return self.inner.handle(callback).await;
+        };
+
+        let _lock = self.locks.lock(&state.shipping_id).await;
+        let last = self
+            .store
+            .last_state(&state.shipping_id)
+            .await
+            .map_err(Into::into)?;
+        let verdict = state.verdict(last.as_ref());
+        if verdict != Verdict::New {
+            tracing::debug!(
+                shipping_id = state.shipping_id,
+                status = %state.status,
+                generated = state.generated,
+                ?verdict,
+                "dropped webhook callback"
+            );
+            return Ok(());
+        }
+
+        self.inner.handle(callback).await?;
+        self.store.record(state).await.map_err(Into::into)
+    }
+}
+
+/// Async locks keyed by shipping id, independent of the runtime. A shipping is in the map
+/// while locked, along with the tasks waiting for it.
+#[derive(Debug, Default)]
+struct ShippingLocks {
+    locked: Mutex<HashMap<String, Vec<Waker>>>,
+}
+
+impl ShippingLocks {
+    fn locked(&self) -> MutexGuard<'_, HashMap<String, Vec<Waker>>> {
+        self.locked.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn lock(&self, shipping_id: &str) -> ShippingLock<'_> {
+        future::poll_fn(|cx| {
+            let mut locked = self.locked();
+            match locked.get_mut(shipping_id) {
+                Some(waiters) => {
+                    if !waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                        waiters.push(cx.waker().clone());
+                    }
+                    Poll::Pending
+                }
+                None => {
+                    locked.insert(shipping_id.to_owned(), Vec::new());
+                    Poll::Ready(())
+                }
+            }
+        })
+        .await;
+        ShippingLock {
+            locks: self,
+            shipping_id: shipping_id.to_owned(),
+        }
+    }
+}
+
+/// Releases the lock of a shipping on drop, waking the tasks waiting for it to compete for
+/// it again.
+struct ShippingLock<'a> {
+    locks: &'a ShippingLocks,
+    shipping_id: String,
+}
+
+impl Drop for ShippingLock<'_> {
+    fn drop(&mut self) {
+        let waiters = self.locks.locked().remove(&self.shipping_id);
+        waiters.into_iter().flatten().for_each(Waker::wake);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::models::CallbackRequestData;
+    use crate::webhook::MemoryWebhookStore;
+
+    use super::*;
+
+    fn state(status: CallbackShippingStatus, generated: &str) -> ShippingState {
+        ShippingState {
+            shipping_id: "64000".to_owned(),
+            status,
+            generated: generated.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_verdict() {
+        use CallbackShippingStatus::*;
+
+        let picked_up = state(PickedUp, "2024-01-01T12:10:00Z");
+        let cases = [
+            (state(PickedUp, "2024-01-01T12:10:00Z"), Verdict::Duplicate),
+            (
+                state(NearPickup, "2024-01-01T12:11:00Z"),
+                Verdict::OutOfOrder,
+            ),
+            (
+                state(NearDropoff, "2024-01-01T12:09:00Z"),
+                Verdict::OutOfOrder,
+            ),
+            (state(PickedUp, "2024-01-01T12:10:00.5Z"), Verdict::New),
+            (state(NearDropoff, "2024-01-01T12:15:00Z"), Verdict::New),
+            (state(Cancelled, "2024-01-01T12:15:00Z"), Verdict::New),
+        ];
+        for (next, verdict) in cases {
+            assert_eq!(next.verdict(Some(&picked_up)), verdict, "{next:?}");
+        }
+
+        let completed = state(Completed, "2024-01-01T12:20:00Z");
+        assert_eq!(
+            state(Cancelled, "2024-01-01T12:30:00Z").verdict(Some(&completed)),
+            Verdict::OutOfOrder
+        );
+        assert_eq!(completed.verdict(None), Verdict::New);
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handler = Deduplicate::new(
+            {
+                let handled = handled.clone();
+                move |callback: CallbackRequest| {
+                    let status = callback.data.unwrap().status.unwrap();
+                    handled.lock().unwrap().push(status);
+                    async { Ok::<_, Infallible>(()) }
+                }
+            },
+            MemoryWebhookStore::new(),
+        );
+        let callback = |status, generated: &str| CallbackRequest {
+            topic: Some(CallbackTopic::ShippingStatus),
+            id: Some("64000".to_owned()),
+            generated: Some(generated.to_owned()),
+            data: Some(Box::new(CallbackRequestData {
+                status: Some(status),
+                ..CallbackRequestData::new()
+            })),
+            ..CallbackRequest::default()
+        };
+
+        use CallbackShippingStatus::*;
+        let deliveries = [
+            callback(Confirmed, "2024-01-01T12:00:00Z"),
+            callback(PickedUp, "2024-01-01T12:10:00Z"),
+            callback(PickedUp, "2024-01-01T12:10:00Z"),
+            callback(NearPickup, "2024-01-01T12:05:00Z"),
+            callback(Completed, "2024-01-01T12:20:00Z"),
+        ];
+        for delivery in deliveries {
+            handler.handle(delivery).await.unwrap();
+        }
+
+        assert_eq!(*handled.lock().unwrap(), [Confirmed, PickedUp, Completed]);
+        assert_eq!(
+            handler.store().last_state("64000").await.unwrap(),
+            Some(state(Completed, "2024-01-01T12:20:00Z"))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_deliveries() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(Deduplicate::new(
+            {
+                let handled = handled.clone();
+                move |callback: CallbackRequest| {
+                    let handled = handled.clone();
+                    async move {
+                        // Long enough for the other delivery to read the last state.
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        handled.lock().unwrap().push(callback.id.unwrap());
+                        Ok::<_, Infallible>(())
+                    }
+                }
+            },
+            MemoryWebhookStore::new(),
+        ));
+        let callback = CallbackRequest {
+            topic: Some(CallbackTopic::ShippingStatus),
+            id: Some("64000".to_owned()),
+            generated: Some("2024-01-01T12:10:00Z".to_owned()),
+            data: Some(Box::new(CallbackRequestData {
+                status: Some(CallbackShippingStatus::PickedUp),
+                ..CallbackRequestData::new()
+            })),
+            ..CallbackRequest::default()
+        };
+
+        let deliveries: Vec<_> = (0..2)
+            .map(|_| {
+                let handler = handler.clone();
+                let callback = callback.clone();
+                tokio::spawn(async move { handler.handle(callback).await })
+            })
+            .collect();
+        for delivery in deliveries {
+            delivery.await.unwrap().unwrap();
+        }
+
+        assert_eq!(*handled.lock().unwrap(), ["64000"]);
+        assert!(handler.locks.locked().is_empty());
+    }
+}
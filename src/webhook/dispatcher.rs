@@ -0,0 +1,322 @@
+use std::{error::Error, fmt, future::Future, pin::Pin};
+
+use crate::models::{CallbackRequest, CallbackTopic};
+use crate::webhook::{
+    CancelledEvent, CompletedEvent, ConfirmedEvent, InProgressEvent, NearDropoffEvent,
+    NearPickupEvent, PickedUpEvent, WebhookEvent, WebhookHandler,
+};
+
+type HandlerFuture = Pin<Box<dyn Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send>>;
+type Handler<E> = Box<dyn Fn(E) -> HandlerFuture + Send + Sync>;
+
+fn handler<E, F, Fut, Err>(handler: F) -> Handler<E>
+where
+    F: Fn(E) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), Err>> + Send + 'static,
+    Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Box::new(move |event| {
+        let handled = handler(event);
+        Box::pin(async move { handled.await.map_err(Into::into) })
+    })
+}
+
+/// Routes callbacks to a handler per shipping status, each receiving a typed event.
+///
+/// Callbacks without a registered handler are acknowledged and dropped, except shipping
+/// status callbacks missing their shipping id or status, which fail unless
+/// [`WebhookDispatcher::on_other`] handles them. Use it as the handler of a
+/// [`crate::webhook::WebhookService`].
+///
+/// ```no_run
+/// use pedidosya_courier_rs::webhook::{WebhookDispatcher, WebhookService, WebhookVerifier};
+///
+/// let dispatcher = WebhookDispatcher::new()
+///     .on_picked_up(|event| async move {
+///         println!("{} is on its way", event.shipping.shipping_id);
+///         Ok::<_, std::io::Error>(())
+///     })
+///     .on_cancelled(|event| async move {
+///         println!("{} cancelled: {:?} ({})", event.shipping.shipping_id, event.cancel_code, event.reason);
+///         Ok::<_, std::io::Error>(())
+///     });
+/// let service = WebhookService::new(WebhookVerifier::new("authorization-key"), dispatcher);
+/// ```
+#[derive(Default)]
+pub struct WebhookDispatcher {
+    confirmed: Option<Handler<ConfirmedEvent>>,
+    in_progress: Option<Handler<InProgressEvent>>,
+    near_pickup: Option<Handler<NearPickupEvent>>,
+    picked_up: Option<Handler<PickedUpEvent>>,
+    near_dropoff: Option<Handler<NearDropoffEvent>>,
+    completed: Option<Handler<CompletedEvent>>,
+    cancelled: Option<Handler<CancelledEvent>>,
+    other: Option<Handler<CallbackRequest>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_confirmed<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(ConfirmedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.confirmed = Some(handler(f));
+        self
+    }
+
+    pub fn on_in_progress<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(InProgressEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.in_progress = Some(handler(f));
+        self
+    }
+
+    pub fn on_near_pickup<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(NearPickupEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.near_pickup = Some(handler(f));
+        self
+    }
+
+    pub fn on_picked_up<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(PickedUpEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.picked_up = Some(handler(f));
+        self
+    }
+
+    pub fn on_near_dropoff<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(NearDropoffEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.near_dropoff = Some(handler(f));
+        self
+    }
+
+    pub fn on_completed<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(CompletedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.completed = Some(handler(f));
+        self
+    }
+
+    pub fn on_cancelled<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(CancelledEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.cancelled = Some(handler(f));
+        self
+    }
+
+    /// Catch-all for [`WebhookEvent::Other`]: topics added after this version of the crate
+    /// and callbacks missing the fields of their status.
+    pub fn on_other<F, Fut, Err>(mut self, f: F) -> Self
+    where
+        F: Fn(CallbackRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Err>> + Send + 'static,
+        Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.other = Some(handler(f));
+        self
+    }
+
+    /// Runs the handler registered for `event`, if any.
+    pub async fn dispatch(&self, event: WebhookEvent) -> Result<(), Box<dyn Error + Send + Sync>> {
+        fn run<E>(handler: &Option<Handler<E>>, event: E) -> Option<HandlerFuture> {
+            handler.as_ref().map(|handler| handler(event))
+        }
+
+        let status = event.status();
+        let malformed = matches!(
+            &event,
+            WebhookEvent::Other(CallbackRequest {
+                topic: None | Some(CallbackTopic::ShippingStatus),
+                ..
+            })
+        );
+        let handled = match event {
+            WebhookEvent::Confirmed(e) => run(&self.confirmed, e),
+            WebhookEvent::InProgress(e) => run(&self.in_progress, e),
+            WebhookEvent::NearPickup(e) => run(&self.near_pickup, e),
+            WebhookEvent::PickedUp(e) => run(&self.picked_up, e),
+            WebhookEvent::NearDropoff(e) => run(&self.near_dropoff, e),
+            WebhookEvent::Completed(e) => run(&self.completed, e),
+            WebhookEvent::Cancelled(e) => run(&self.cancelled, e),
+            WebhookEvent::Other(callback) => run(&self.other, callback),
+        };
+        match handled {
+            Some(handled) => handled.await,
+            None if malformed => {
+                tracing::warn!("shipping status callback without shipping id or status");
+                Err("shipping status callback without shipping id or status".into())
+            }
+            None => {
+                tracing::debug!(?status, "no handler registered for webhook event");
+                Ok(())
+            }
+        }
+    }
+}
+
+impl WebhookHandler for WebhookDispatcher {
+    async fn handle(&self, callback: CallbackRequest) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.dispatch(WebhookEvent::from(callback)).await
+    }
+}
+
+impl fmt::Debug for WebhookDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookDispatcher")
+            .field("on_confirmed", &self.confirmed.is_some())
+            .field("on_in_progress", &self.in_progress.is_some())
+            .field("on_near_pickup", &self.near_pickup.is_some())
+            .field("on_picked_up", &self.picked_up.is_some())
+            .field("on_near_dropoff", &self.near_dropoff.is_some())
+            .field("on_completed", &self.completed.is_some())
+            .field("on_cancelled", &self.cancelled.is_some())
+            .field("on_other", &self.other.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::models::{CallbackShippingStatus, CancelCode};
+
+    use super::*;
+
+    fn callback(json: &str) -> CallbackRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let dispatcher = WebhookDispatcher::new()
+            .on_picked_up({
+                let log = log.clone();
+                move |event| {
+                    log.lock()
+                        .unwrap()
+                        .push(format!("picked up {}", event.shipping.shipping_id));
+                    async { Ok::<_, std::io::Error>(()) }
+                }
+            })
+            .on_cancelled({
+                let log = log.clone();
+                move |event| {
+                    log.lock().unwrap().push(format!(
+                        "cancelled {:?} {}",
+                        event.cancel_code, event.reason
+                    ));
+                    async { Ok::<_, std::io::Error>(()) }
+                }
+            })
+            .on_other({
+                let log = log.clone();
+                move |callback| {
+                    log.lock()
+                        .unwrap()
+                        .push(format!("other {:?}", callback.topic));
+                    async { Ok::<_, std::io::Error>(()) }
+                }
+            });
+
+        let callbacks = [
+            r#"{"topic": "SHIPPING_STATUS", "id": "1", "generated": "2024-01-01T12:00:00Z", "data": {"status": "PICKED_UP"}}"#,
+            r#"{"topic": "SHIPPING_STATUS", "id": "1", "generated": "2024-01-01T12:00:00Z",
+                "data": {"status": "CANCELLED", "cancelCode": "BAD_WEATHER", "cancelReason": "Lluvia"}}"#,
+            r#"{"topic": "SHIPPING_STATUS", "id": "1", "generated": "2024-01-01T12:00:00Z", "data": {"status": "COMPLETED"}}"#,
+            r#"{"topic": "RIDER_LOCATION", "id": "1"}"#,
+            r#"{"topic": "SHIPPING_STATUS", "data": {"status": "CONFIRMED"}}"#,
+        ];
+        for json in callbacks {
+            dispatcher.handle(callback(json)).await.unwrap();
+        }
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "picked up 1",
+                "cancelled Some(BadWeather) Lluvia",
+                "other Some(Unknown(\"RIDER_LOCATION\"))",
+                "other Some(ShippingStatus)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_webhook_event() {
+        let event = WebhookEvent::from(callback(
+            r#"{"topic": "SHIPPING_STATUS", "id": "1", "referenceId": "order-1", "generated": "2024-01-01T12:00:00Z",
+                "data": {"status": "CANCELLED", "cancelCode": "USER_CANCELLED"}}"#,
+        ));
+
+        assert_eq!(event.status(), Some(CallbackShippingStatus::Cancelled));
+        assert_eq!(
+            event.shipping().unwrap().reference_id.as_deref(),
+            Some("order-1")
+        );
+        let WebhookEvent::Cancelled(cancelled) = event else {
+            panic!("expected a cancelled event");
+        };
+        assert_eq!(cancelled.cancel_code, Some(CancelCode::UserCancelled));
+
+        let event = WebhookEvent::from(callback(
+            r#"{"topic": "SHIPPING_STATUS", "id": "1", "data": {"status": "CANCELLED"}}"#,
+        ));
+        let WebhookEvent::Cancelled(cancelled) = event else {
+            panic!("expected a cancelled event");
+        };
+        assert_eq!(cancelled.cancel_code, None);
+        assert_eq!(cancelled.shipping.generated, None);
+
+        let event = WebhookEvent::from(callback(
+            r#"{"topic": "SHIPPING_STATUS", "data": {"status": "CANCELLED"}}"#,
+        ));
+        assert!(matches!(
+            event,
+            WebhookEvent::Other(CallbackRequest {
+                topic: Some(CallbackTopic::ShippingStatus),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unhandled_malformed_callback_fails() {
+        let dispatcher = WebhookDispatcher::new();
+
+        dispatcher
+            .handle(callback(r#"{"topic": "RIDER_LOCATION", "id": "1"}"#))
+            .await
+            .unwrap();
+        dispatcher
+            .handle(callback(r#"{"topic": "SHIPPING_STATUS", "id": "1"}"#))
+            .await
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,167 @@
+use crate::models::{
+    CallbackRequest, CallbackRequestData, CallbackShippingStatus, CallbackTopic, CancelCode,
+};
+
+/// Fields shared by every shipping status callback.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShippingEvent {
+    pub shipping_id: String,
+    /// The `reference_id` the shipping was created with, when PedidosYa sends it back.
+    pub reference_id: Option<String>,
+    /// When the status change happened (ISO 8601, UTC), if PedidosYa sent it.
+    pub generated: Option<String>,
+    /// When the callback was sent (ISO 8601, UTC).
+    pub transmitted: Option<String>,
+}
+
+/// The shipping was confirmed and awaits a rider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfirmedEvent {
+    pub shipping: ShippingEvent,
+    pub estimated_pick_up_time: Option<String>,
+    pub estimated_drop_off_time: Option<String>,
+}
+
+/// A rider has been assigned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InProgressEvent {
+    pub shipping: ShippingEvent,
+    pub estimated_pick_up_time: Option<String>,
+    pub estimated_drop_off_time: Option<String>,
+}
+
+/// The rider is close to the pickup point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NearPickupEvent {
+    pub shipping: ShippingEvent,
+    pub estimated_pick_up_time: Option<String>,
+    pub estimated_drop_off_time: Option<String>,
+}
+
+/// The rider picked up the items.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickedUpEvent {
+    pub shipping: ShippingEvent,
+    pub estimated_drop_off_time: Option<String>,
+}
+
+/// The rider is close to the dropoff point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NearDropoffEvent {
+    pub shipping: ShippingEvent,
+    pub estimated_drop_off_time: Option<String>,
+}
+
+/// The items were delivered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompletedEvent {
+    pub shipping: ShippingEvent,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CancelledEvent {
+    pub shipping: ShippingEvent,
+    /// Why the shipping was cancelled, if PedidosYa sent a code.
+    pub cancel_code: Option<CancelCode>,
+    /// Spanish description of `cancel_code`.
+    pub reason: String,
+}
+
+/// A [`CallbackRequest`] with the fields required by its topic and status checked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WebhookEvent {
+    Confirmed(ConfirmedEvent),
+    InProgress(InProgressEvent),
+    NearPickup(NearPickupEvent),
+    PickedUp(PickedUpEvent),
+    NearDropoff(NearDropoffEvent),
+    Completed(CompletedEvent),
+    Cancelled(CancelledEvent),
+    /// Callbacks of a topic this version does not know, and shipping status callbacks
+    /// missing the shipping id or status.
+    Other(CallbackRequest),
+}
+
+impl WebhookEvent {
+    pub fn shipping(&self) -> Option<&ShippingEvent> {
+        match self {
+            WebhookEvent::Confirmed(e) => Some(&e.shipping),
+            WebhookEvent::InProgress(e) => Some(&e.shipping),
+            WebhookEvent::NearPickup(e) => Some(&e.shipping),
+            WebhookEvent::PickedUp(e) => Some(&e.shipping),
+            WebhookEvent::NearDropoff(e) => Some(&e.shipping),
+            WebhookEvent::Completed(e) => Some(&e.shipping),
+            WebhookEvent::Cancelled(e) => Some(&e.shipping),
+            WebhookEvent::Other(_) => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<CallbackShippingStatus> {
+        match self {
+            WebhookEvent::Confirmed(_) => Some(CallbackShippingStatus::Confirmed),
+            WebhookEvent::InProgress(_) => Some(CallbackShippingStatus::InProgress),
+            WebhookEvent::NearPickup(_) => Some(CallbackShippingStatus::NearPickup),
+            WebhookEvent::PickedUp(_) => Some(CallbackShippingStatus::PickedUp),
+            WebhookEvent::NearDropoff(_) => Some(CallbackShippingStatus::NearDropoff),
+            WebhookEvent::Completed(_) => Some(CallbackShippingStatus::Completed),
+            WebhookEvent::Cancelled(_) => Some(CallbackShippingStatus::Cancelled),
+            WebhookEvent::Other(_) => None,
+        }
+    }
+
+    fn shipping_status(callback: &CallbackRequest) -> Option<Self> {
+        if !matches!(callback.topic, None | Some(CallbackTopic::ShippingStatus)) {
+            return None;
+        }
+        let data: &CallbackRequestData = callback.data.as_deref()?;
+        let shipping = ShippingEvent {
+            shipping_id: callback.id.clone()?,
+            reference_id: callback.reference_id.clone(),
+            generated: callback.generated.clone(),
+            transmitted: callback.transmitted.clone(),
+        };
+        let estimated_pick_up_time = data.estimated_pick_up_time.clone();
+        let estimated_drop_off_time = data.estimated_drop_off_time.clone();
+
+        let event = match data.status? {
+            CallbackShippingStatus::Confirmed => WebhookEvent::Confirmed(ConfirmedEvent {
+                shipping,
+                estimated_pick_up_time,
+                estimated_drop_off_time,
+            }),
+            CallbackShippingStatus::InProgress => WebhookEvent::InProgress(InProgressEvent {
+                shipping,
+                estimated_pick_up_time,
+                estimated_drop_off_time,
+            }),
+            CallbackShippingStatus::NearPickup => WebhookEvent::NearPickup(NearPickupEvent {
+                shipping,
+                estimated_pick_up_time,
+                estimated_drop_off_time,
+            }),
+            CallbackShippingStatus::PickedUp => WebhookEvent::PickedUp(PickedUpEvent {
+                shipping,
+                estimated_drop_off_time,
+            }),
+            CallbackShippingStatus::NearDropoff => WebhookEvent::NearDropoff(NearDropoffEvent {
+                shipping,
+                estimated_drop_off_time,
+            }),
+            CallbackShippingStatus::Completed => {
+                WebhookEvent::Completed(CompletedEvent { shipping })
+            }
+            CallbackShippingStatus::Cancelled => WebhookEvent::Cancelled(CancelledEvent {
+                shipping,
+                cancel_code: data.cancel_code.clone(),
+                reason: data.cancel_reason.clone().unwrap_or_default(),
+            }),
+        };
+        Some(event)
+    }
+}
+
+impl From<CallbackRequest> for WebhookEvent {
+    fn from(callback: CallbackRequest) -> Self {
+        Self::shipping_status(&callback).unwrap_or(WebhookEvent::Other(callback))
+    }
+}
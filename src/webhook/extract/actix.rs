@@ -0,0 +1,128 @@
+use std::{future::Future, pin::Pin};
+
+use actix_web::{
+    FromRequest, HttpRequest, ResponseError,
+    body::{self, BodyStream},
+    dev::Payload,
+    error::ErrorInternalServerError,
+    http::{
+        Method, StatusCode,
+        header::{AUTHORIZATION, CONTENT_LENGTH},
+    },
+    web::Data,
+};
+
+use crate::webhook::{VerifiedCallback, WebhookError, WebhookVerifier};
+
+/// Verifies the request with the `web::Data<WebhookVerifier>` of the app. Requests reaching
+/// an app without one are answered with `500`.
+///
+/// ```no_run
+/// use actix_web::{App, web};
+/// use pedidosya_courier_rs::webhook::{VerifiedCallback, WebhookVerifier};
+///
+/// async fn webhook(VerifiedCallback(callback): VerifiedCallback) -> String {
+///     format!("received {:?}", callback.id)
+/// }
+///
+/// let app = App::new()
+///     .app_data(web::Data::new(WebhookVerifier::new("authorization-key")))
+///     .route("/webhooks", web::post().to(webhook));
+/// ```
+impl FromRequest for VerifiedCallback {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, actix_web::Error>>>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let Some(verifier) = request.app_data::<Data<WebhookVerifier>>().cloned() else {
+            tracing::error!("no WebhookVerifier registered as app data");
+            return Box::pin(async {
+                Err(ErrorInternalServerError(
+                    "webhook verifier is not configured",
+                ))
+            });
+        };
+        let headers = request.headers();
+        let checked = if request.method() != Method::POST {
+            Err(WebhookError::MethodNotAllowed)
+        } else {
+            verifier
+                .authorize_value(headers.get(AUTHORIZATION).map(|v| v.as_bytes()))
+                .and_then(|()| {
+                    verifier.check_content_length(headers.get(CONTENT_LENGTH).map(|v| v.as_bytes()))
+                })
+        };
+        let payload = payload.take();
+
+        Box::pin(async move {
+            let verified = async {
+                checked?;
+                let body = body::to_bytes_limited(BodyStream::new(payload), verifier.body_limit())
+                    .await
+                    .map_err(|_| verifier.payload_too_large())?
+                    .map_err(|e| WebhookError::Body(e.into()))?;
+                verifier.parse(&body)
+            };
+            match verified.await {
+                Ok(callback) => Ok(VerifiedCallback(callback)),
+                Err(e) => {
+                    tracing::warn!(status = e.status().as_u16(), error = %e, "rejected webhook callback");
+                    Err(e.into())
+                }
+            }
+        })
+    }
+}
+
+impl ResponseError for WebhookError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status().as_u16()).unwrap_or(StatusCode::BAD_REQUEST)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test, web};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_actix_extractor() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(WebhookVerifier::new("key").max_body_size(128)))
+                .route(
+                    "/webhooks",
+                    web::post().to(|VerifiedCallback(callback): VerifiedCallback| async move {
+                        callback.id.unwrap_or_default()
+                    }),
+                ),
+        )
+        .await;
+        let request = |key: &str, body: &str| {
+            test::TestRequest::post()
+                .uri("/webhooks")
+                .insert_header((AUTHORIZATION, key))
+                .set_payload(body.to_owned())
+                .to_request()
+        };
+
+        let callback =
+            r#"{"topic": "SHIPPING_STATUS", "id": "64000", "data": {"status": "COMPLETED"}}"#;
+        let response = test::call_service(&app, request("key", callback)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(test::read_body(response).await, "64000");
+
+        let rejected = [
+            (request("wrong-key", callback), StatusCode::UNAUTHORIZED),
+            (request("key", "{\"topic\": 1}"), StatusCode::BAD_REQUEST),
+            (
+                request("key", &" ".repeat(256)),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+        ];
+        for (request, status) in rejected {
+            assert_eq!(test::call_service(&app, request).await.status(), status);
+        }
+    }
+}
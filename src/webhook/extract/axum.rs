@@ -0,0 +1,94 @@
+use axum::{
+    extract::{FromRef, FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+
+use crate::webhook::{VerifiedCallback, WebhookError, WebhookVerifier};
+
+/// Verifies the request with the [`WebhookVerifier`] of the router state.
+///
+/// ```no_run
+/// use axum::{Router, routing::post};
+/// use pedidosya_courier_rs::webhook::{VerifiedCallback, WebhookVerifier};
+///
+/// async fn webhook(VerifiedCallback(callback): VerifiedCallback) {
+///     println!("shipping {:?} is now {:?}", callback.id, callback.data);
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/webhooks", post(webhook))
+///     .with_state(WebhookVerifier::new("authorization-key"));
+/// ```
+impl<S> FromRequest<S> for VerifiedCallback
+where
+    S: Send + Sync,
+    WebhookVerifier: FromRef<S>,
+{
+    type Rejection = WebhookError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, WebhookError> {
+        let verifier = WebhookVerifier::from_ref(state);
+        match verifier.verify_request(request).await {
+            Ok(callback) => Ok(VerifiedCallback(callback)),
+            Err(e) => {
+                tracing::warn!(status = e.status().as_u16(), error = %e, "rejected webhook callback");
+                Err(e)
+            }
+        }
+    }
+}
+
+impl IntoResponse for WebhookError {
+    fn into_response(self) -> Response {
+        (self.status(), self.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, body::Body, routing::post};
+    use http::{StatusCode, header::AUTHORIZATION};
+    use tower_service::Service;
+
+    use super::*;
+
+    fn request(key: &str, body: &str) -> Request {
+        Request::post("/webhooks")
+            .header(AUTHORIZATION, key)
+            .body(Body::from(body.to_owned()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_axum_extractor() {
+        let mut app = Router::new()
+            .route(
+                "/webhooks",
+                post(|VerifiedCallback(callback): VerifiedCallback| async move {
+                    callback.id.unwrap_or_default()
+                }),
+            )
+            .with_state(WebhookVerifier::new("key").max_body_size(128));
+
+        let callback =
+            r#"{"topic": "SHIPPING_STATUS", "id": "64000", "data": {"status": "COMPLETED"}}"#;
+        let response = app.call(request("key", callback)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "64000");
+
+        let rejected = [
+            (request("wrong-key", callback), StatusCode::UNAUTHORIZED),
+            (request("key", "{\"topic\": 1}"), StatusCode::BAD_REQUEST),
+            (
+                request("key", &" ".repeat(256)),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+        ];
+        for (request, status) in rejected {
+            assert_eq!(app.call(request).await.unwrap().status(), status);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Extractors handing verified callbacks to axum and actix-web handlers.
+
+#[cfg(feature = "webhook-actix")]
+mod actix;
+#[cfg(feature = "webhook-axum")]
+mod axum;
+
+use crate::models::CallbackRequest;
+
+/// A callback whose `Authorization` header was accepted by the [`crate::webhook::WebhookVerifier`]
+/// of the application, and whose body was parsed.
+///
+/// Rejected requests are answered with [`crate::webhook::WebhookError::status`] before the
+/// handler runs. axum takes the verifier from the router state, actix-web from the
+/// `web::Data<WebhookVerifier>` of the app.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedCallback(pub CallbackRequest);
+
+impl VerifiedCallback {
+    pub fn into_inner(self) -> CallbackRequest {
+        self.0
+    }
+}
@@ -0,0 +1,654 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    error::Error,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{sync::Notify, task::JoinHandle};
+
+use crate::models::CallbackRequest;
+use crate::webhook::WebhookHandler;
+
+/// A callback accepted by the [`WebhookInbox`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InboxEntry {
+    /// Sequence number assigned by the inbox.
+    pub id: u64,
+    pub callback: CallbackRequest,
+}
+
+/// An entry whose handler kept failing, kept until it is replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadLetter {
+    pub entry: InboxEntry,
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: String,
+}
+
+/// Line of the inbox file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record {
+    /// First line of a compacted file, keeping ids from being reused.
+    Sequence {
+        next_id: u64,
+    },
+    Received {
+        id: u64,
+        callback: CallbackRequest,
+    },
+    Processed {
+        id: u64,
+    },
+    DeadLettered {
+        id: u64,
+        attempts: u32,
+        error: String,
+    },
+    Replayed {
+        id: u64,
+    },
+}
+
+#[derive(Debug)]
+struct Log {
+    file: File,
+    /// Length of the file up to the last complete record.
+    len: u64,
+    /// Whether a failed append may have left a partial line the file could not be
+    /// truncated back from.
+    torn: bool,
+    next_id: u64,
+    /// Entries not processed yet, including the ones being processed.
+    pending: BTreeMap<u64, CallbackRequest>,
+    /// Pending entries no worker has picked up.
+    queue: VecDeque<u64>,
+    dead: BTreeMap<u64, DeadLetter>,
+}
+
+impl Log {
+    fn apply(&mut self, record: Record) {
+        match record {
+            Record::Sequence { next_id } => {
+                self.next_id = self.next_id.max(next_id);
+            }
+            Record::Received { id, callback } => {
+                self.next_id = self.next_id.max(id + 1);
+                self.pending.insert(id, callback);
+            }
+            Record::Processed { id } => {
+                self.pending.remove(&id);
+            }
+            Record::DeadLettered {
+                id,
+                attempts,
+                error,
+            } => {
+                if let Some(callback) = self.pending.remove(&id) {
+                    let entry = InboxEntry { id, callback };
+                    self.dead.insert(
+                        id,
+                        DeadLetter {
+                            entry,
+                            attempts,
+                            error,
+                        },
+                    );
+                }
+            }
+            Record::Replayed { id } => {
+                if let Some(dead) = self.dead.remove(&id) {
+                    self.pending.insert(id, dead.entry.callback);
+                }
+            }
+        }
+    }
+
+    /// Appends `record` to the file and syncs it before applying it.
+    ///
+    /// A failed append is truncated away, so the next record does not end up on the same
+    /// line as a partial one; if that fails as well, the next record starts a new line.
+    fn append(&mut self, record: Record) -> io::Result<()> {
+        let mut line = String::new();
+        if self.torn {
+            // The partial line is still in the file, past the tracked length.
+            self.len = self.file.metadata()?.len();
+            line.push('\n');
+        }
+        line.push_str(&serde_json::to_string(&record)?);
+        line.push('\n');
+
+        let written = self
+            .file
+            .write_all(line.as_bytes())
+            .and_then(|()| self.file.sync_data());
+        if let Err(e) = written {
+            self.torn = self.file.set_len(self.len).is_err();
+            return Err(e);
+        }
+        self.len += line.len() as u64;
+        self.torn = false;
+        self.apply(record);
+        Ok(())
+    }
+
+    fn next(&mut self) -> Option<InboxEntry> {
+        while let Some(id) = self.queue.pop_front() {
+            if let Some(callback) = self.pending.get(&id) {
+                return Some(InboxEntry {
+                    id,
+                    callback: callback.clone(),
+                });
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    path: PathBuf,
+    log: Mutex<Log>,
+    notify: Notify,
+}
+
+impl Shared {
+    fn log(&self) -> MutexGuard<'_, Log> {
+        self.log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Durable queue between the webhook endpoint and the code processing callbacks.
+///
+/// As a [`WebhookHandler`] it appends every callback to an append-only file, synced to
+/// disk, before the callback is acknowledged. Workers started with
+/// [`WebhookInbox::spawn_workers`] then run the actual handler, retrying it, and move the
+/// entries that keep failing to the dead letters, where they can be inspected and replayed.
+///
+/// Entries are processed at least once: an entry being handled when the process stops is
+/// handled again after [`WebhookInbox::open`]. The appends made as a handler and by the
+/// workers run on the blocking thread pool; the other file operations block the caller.
+///
+/// ```no_run
+/// # fn run() -> std::io::Result<()> {
+/// use pedidosya_courier_rs::webhook::{
+///     InboxConfig, WebhookDispatcher, WebhookInbox, WebhookService, WebhookVerifier,
+/// };
+///
+/// let inbox = WebhookInbox::open("/var/lib/courier/webhooks.jsonl")?;
+/// let _workers = inbox.spawn_workers(WebhookDispatcher::new(), InboxConfig::new());
+/// let service = WebhookService::new(WebhookVerifier::new("authorization-key"), inbox);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebhookInbox {
+    shared: Arc<Shared>,
+}
+
+impl WebhookInbox {
+    /// Opens the inbox at `path`, creating the file if needed. Entries left pending by a
+    /// previous run are queued again.
+    ///
+    /// Lines that can not be parsed, such as one cut short by a crash, are skipped.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+
+        let len = file.metadata()?.len();
+        let mut log = Log {
+            file,
+            len,
+            torn: false,
+            next_id: 1,
+            pending: BTreeMap::new(),
+            queue: VecDeque::new(),
+            dead: BTreeMap::new(),
+        };
+        for line in content.lines() {
+            match serde_json::from_str(line) {
+                Ok(record) => log.apply(record),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipped invalid webhook inbox line");
+                }
+            }
+        }
+        log.queue = log.pending.keys().copied().collect();
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                path,
+                log: Mutex::new(log),
+                notify: Notify::new(),
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+
+    /// Persists `callback` and queues it for the workers. Returns the id of the new entry.
+    pub fn push(&self, callback: CallbackRequest) -> io::Result<u64> {
+        let mut log = self.shared.log();
+        let id = log.next_id;
+        log.append(Record::Received { id, callback })?;
+        log.queue.push_back(id);
+        drop(log);
+
+        self.shared.notify.notify_one();
+        Ok(id)
+    }
+
+    /// Entries not processed yet, including the ones being processed.
+    pub fn pending(&self) -> Vec<InboxEntry> {
+        self.shared
+            .log()
+            .pending
+            .iter()
+            .map(|(&id, callback)| InboxEntry {
+                id,
+                callback: callback.clone(),
+            })
+            .collect()
+    }
+
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.shared.log().dead.values().cloned().collect()
+    }
+
+    /// Queues the dead letter `id` again. Returns `false` if there is no such dead letter.
+    pub fn replay(&self, id: u64) -> io::Result<bool> {
+        let mut log = self.shared.log();
+        if !log.dead.contains_key(&id) {
+            return Ok(false);
+        }
+        log.append(Record::Replayed { id })?;
+        log.queue.push_back(id);
+        drop(log);
+
+        self.shared.notify.notify_one();
+        Ok(true)
+    }
+
+    /// Queues every dead letter again, returning how many there were.
+    pub fn replay_all(&self) -> io::Result<usize> {
+        let ids: Vec<u64> = self.shared.log().dead.keys().copied().collect();
+        for &id in &ids {
+            self.replay(id)?;
+        }
+        Ok(ids.len())
+    }
+
+    /// Rewrites the file with only the pending entries and the dead letters. The next
+    /// entry id is kept, so ids are not reused after compaction.
+    pub fn compact(&self) -> io::Result<()> {
+        let mut log = self.shared.log();
+        let compacted = self.shared.path.with_extension("compact");
+        {
+            let mut file = File::create(&compacted)?;
+            let mut write = |record: &Record| -> io::Result<()> {
+                writeln!(file, "{}", serde_json::to_string(record)?)
+            };
+            write(&Record::Sequence {
+                next_id: log.next_id,
+            })?;
+            let dead = log
+                .dead
+                .values()
+                .map(|dead| (&dead.entry.id, &dead.entry.callback));
+            for (&id, callback) in log.pending.iter().chain(dead) {
+                write(&Record::Received {
+                    id,
+                    callback: callback.clone(),
+                })?;
+            }
+            for dead in log.dead.values() {
+                write(&Record::DeadLettered {
+                    id: dead.entry.id,
+                    attempts: dead.attempts,
+                    error: dead.error.clone(),
+                })?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&compacted, &self.shared.path)?;
+        sync_parent_dir(&self.shared.path)?;
+        log.file = OpenOptions::new().append(true).open(&self.shared.path)?;
+        log.len = log.file.metadata()?.len();
+        log.torn = false;
+        Ok(())
+    }
+
+    /// Starts `config.workers` tasks on the current tokio runtime, each handling queued
+    /// entries with `handler`. The workers stop when the returned [`InboxWorkers`] is dropped.
+    pub fn spawn_workers<H: WebhookHandler>(
+        &self,
+        handler: H,
+        config: InboxConfig,
+    ) -> InboxWorkers {
+        let handler = Arc::new(handler);
+        let handles = (0..config.workers.max(1))
+            .map(|_| tokio::spawn(work(self.shared.clone(), handler.clone(), config.clone())))
+            .collect();
+        InboxWorkers { handles }
+    }
+}
+
+/// Syncs the directory holding `path`, making a rename to `path` durable.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir)?.sync_all()
+}
+
+/// Directories can not be opened as files to be synced on this platform.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+impl WebhookHandler for WebhookInbox {
+    async fn handle(&self, callback: CallbackRequest) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let inbox = self.clone();
+        tokio::task::spawn_blocking(move || inbox.push(callback)).await??;
+        Ok(())
+    }
+}
+
+/// Appends `record` on the blocking thread pool, as syncing the file may take a while.
+async fn append(shared: &Arc<Shared>, record: Record) -> io::Result<()> {
+    let shared = shared.clone();
+    tokio::task::spawn_blocking(move || shared.log().append(record))
+        .await
+        .map_err(io::Error::other)?
+}
+
+async fn work<H: WebhookHandler>(shared: Arc<Shared>, handler: Arc<H>, config: InboxConfig) {
+    loop {
+        let next = shared.log().next();
+        let Some(entry) = next else {
+            shared.notify.notified().await;
+            continue;
+        };
+
+        let mut attempt = 1;
+        let record = loop {
+            match handler.handle(entry.callback.clone()).await {
+                Ok(()) => break Record::Processed { id: entry.id },
+                Err(e) if attempt < config.max_attempts => {
+                    let delay = config.delay(attempt);
+                    tracing::warn!(entry = entry.id, attempt, ?delay, error = %e, "webhook handler failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    tracing::error!(entry = entry.id, attempt, error = %e, "webhook handler failed, moving entry to dead letters");
+                    break Record::DeadLettered {
+                        id: entry.id,
+                        attempts: attempt,
+                        error: e.to_string(),
+                    };
+                }
+            }
+        };
+        // The entry stays pending until its outcome is on disk, so the append is retried
+        // rather than leaving the entry to no worker until the inbox is opened again.
+        let mut attempt = 1;
+        while let Err(e) = append(&shared, record.clone()).await {
+            let delay = config.delay(attempt);
+            tracing::error!(entry = entry.id, attempt, ?delay, error = %e, "could not update webhook inbox, retrying");
+            tokio::time::sleep(delay).await;
+            attempt = attempt.saturating_add(1);
+        }
+    }
+}
+
+/// How [`WebhookInbox::spawn_workers`] processes entries.
+#[derive(Clone, Debug)]
+pub struct InboxConfig {
+    workers: usize,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for InboxConfig {
+    fn default() -> Self {
+        Self {
+            workers: 4,
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl InboxConfig {
+    /// 4 workers, 5 attempts per entry, backing off from 1 second up to 1 minute.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Attempts, the first one included, before an entry is moved to the dead letters.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Delay before the first retry, doubled on every following one up to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+/// Workers started by [`WebhookInbox::spawn_workers`], aborted when dropped.
+#[derive(Debug)]
+pub struct InboxWorkers {
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Drop for InboxWorkers {
+    fn drop(&mut self) {
+        for handle in &self.handles {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn callback(id: &str) -> CallbackRequest {
+        CallbackRequest {
+            id: Some(id.to_owned()),
+            ..CallbackRequest::default()
+        }
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..500 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    #[tokio::test]
+    async fn test_inbox() {
+        let dir = std::env::temp_dir().join(format!("pedidosya-inbox-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("inbox.jsonl");
+
+        let inbox = WebhookInbox::open(&path).unwrap();
+        inbox.handle(callback("flaky")).await.unwrap();
+        inbox.handle(callback("broken")).await.unwrap();
+        inbox.handle(callback("ok")).await.unwrap();
+        assert_eq!(WebhookInbox::open(&path).unwrap().pending().len(), 3);
+
+        let flaky_attempts = Arc::new(AtomicU32::new(0));
+        let workers = inbox.spawn_workers(
+            {
+                let flaky_attempts = flaky_attempts.clone();
+                move |callback: CallbackRequest| {
+                    let result = match callback.id.as_deref() {
+                        Some("broken") => Err("database unavailable"),
+                        Some("flaky") if flaky_attempts.fetch_add(1, Ordering::SeqCst) == 0 => {
+                            Err("timeout")
+                        }
+                        _ => Ok(()),
+                    };
+                    async move { result }
+                }
+            },
+            InboxConfig::new()
+                .workers(2)
+                .max_attempts(3)
+                .backoff(Duration::from_millis(1), Duration::from_millis(1)),
+        );
+        wait_until(|| inbox.pending().is_empty()).await;
+        drop(workers);
+
+        assert_eq!(flaky_attempts.load(Ordering::SeqCst), 2);
+        let dead = inbox.dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].entry.callback.id.as_deref(), Some("broken"));
+        assert_eq!(dead[0].attempts, 3);
+        assert_eq!(dead[0].error, "database unavailable");
+
+        inbox.compact().unwrap();
+        let inbox = WebhookInbox::open(&path).unwrap();
+        assert!(inbox.pending().is_empty());
+        assert_eq!(inbox.dead_letters(), dead);
+
+        assert_eq!(inbox.replay_all().unwrap(), 1);
+        let _workers =
+            inbox.spawn_workers(|_| async { Ok::<_, io::Error>(()) }, InboxConfig::new());
+        wait_until(|| inbox.pending().is_empty()).await;
+        assert!(inbox.dead_letters().is_empty());
+        assert!(!inbox.replay(dead[0].entry.id).unwrap());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_record_is_retried() {
+        let dir =
+            std::env::temp_dir().join(format!("pedidosya-inbox-retry-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("inbox.jsonl");
+
+        let inbox = WebhookInbox::open(&path).unwrap();
+        inbox.handle(callback("ok")).await.unwrap();
+        // A read-only handle makes appending the outcome of the entry fail.
+        inbox.shared.log().file = File::open(&path).unwrap();
+
+        let handled = Arc::new(AtomicU32::new(0));
+        let _workers = inbox.spawn_workers(
+            {
+                let handled = handled.clone();
+                move |_| {
+                    handled.fetch_add(1, Ordering::SeqCst);
+                    async { Ok::<_, io::Error>(()) }
+                }
+            },
+            InboxConfig::new().backoff(Duration::from_millis(1), Duration::from_millis(5)),
+        );
+        wait_until(|| handled.load(Ordering::SeqCst) == 1).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(inbox.pending().len(), 1);
+
+        inbox.shared.log().file = OpenOptions::new().append(true).open(&path).unwrap();
+        wait_until(|| inbox.pending().is_empty()).await;
+        assert_eq!(handled.load(Ordering::SeqCst), 1);
+        assert!(WebhookInbox::open(&path).unwrap().pending().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_torn_write() {
+        let dir = std::env::temp_dir().join(format!("pedidosya-inbox-torn-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("inbox.jsonl");
+
+        let inbox = WebhookInbox::open(&path).unwrap();
+        inbox.push(callback("first")).unwrap();
+        // An append failing halfway, with a file that can not be truncated either.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"record":"received","id":2,"call"#)
+            .unwrap();
+        inbox.shared.log().file = File::open(&path).unwrap();
+        inbox.push(callback("lost")).unwrap_err();
+
+        inbox.shared.log().file = OpenOptions::new().append(true).open(&path).unwrap();
+        inbox.push(callback("second")).unwrap();
+
+        let ids: Vec<_> = WebhookInbox::open(&path)
+            .unwrap()
+            .pending()
+            .into_iter()
+            .map(|entry| entry.callback.id.unwrap())
+            .collect();
+        assert_eq!(ids, ["first", "second"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_compaction_keeps_ids() {
+        let dir =
+            std::env::temp_dir().join(format!("pedidosya-inbox-compact-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("inbox.jsonl");
+
+        let inbox = WebhookInbox::open(&path).unwrap();
+        for name in ["first", "second"] {
+            let id = inbox.push(callback(name)).unwrap();
+            inbox.shared.log().append(Record::Processed { id }).unwrap();
+        }
+        inbox.compact().unwrap();
+
+        let inbox = WebhookInbox::open(&path).unwrap();
+        assert!(inbox.pending().is_empty());
+        assert_eq!(inbox.push(callback("third")).unwrap(), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
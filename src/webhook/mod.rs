@@ -0,0 +1,38 @@
+//! Receiving the webhook callbacks PedidosYa sends to the URLs registered with
+//! [`crate::models::WebhooksConfigModel`].
+//!
+//! [`WebhookVerifier`] checks the `Authorization` header against the configured
+//! `authorization_key`s and parses the body into a [`crate::models::CallbackRequest`].
+//! [`WebhookService`] wraps it, together with a [`WebhookHandler`], in a `tower` service
+//! that can be served with hyper or mounted in any tower based router.
+//! [`WebhookDispatcher`] is a handler routing each shipping status to its own typed handler,
+//! and [`Deduplicate`] drops repeated and out of order callbacks in front of it.
+//! With the `webhook-inbox` feature, `WebhookInbox` persists callbacks before acknowledging
+//! them and processes them in the background.
+//! With the `webhook-axum` and `webhook-actix` features, `VerifiedCallback` is an extractor
+//! running the same verification in axum and actix-web handlers.
+
+mod dedup;
+mod dispatcher;
+mod event;
+#[cfg(any(feature = "webhook-axum", feature = "webhook-actix"))]
+mod extract;
+#[cfg(feature = "webhook-inbox")]
+mod inbox;
+mod service;
+mod store;
+mod verifier;
+
+pub use dedup::{Deduplicate, ShippingState, Verdict, WebhookStore};
+pub use dispatcher::WebhookDispatcher;
+pub use event::{
+    CancelledEvent, CompletedEvent, ConfirmedEvent, InProgressEvent, NearDropoffEvent,
+    NearPickupEvent, PickedUpEvent, ShippingEvent, WebhookEvent,
+};
+#[cfg(any(feature = "webhook-axum", feature = "webhook-actix"))]
+pub use extract::VerifiedCallback;
+#[cfg(feature = "webhook-inbox")]
+pub use inbox::{DeadLetter, InboxConfig, InboxEntry, InboxWorkers, WebhookInbox};
+pub use service::{WebhookHandler, WebhookService};
+pub use store::{FileWebhookStore, MemoryWebhookStore};
+pub use verifier::{DEFAULT_MAX_BODY_SIZE, WebhookError, WebhookVerifier};
@@ -0,0 +1,213 @@
+use std::{
+    convert::Infallible,
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{Request, Response, StatusCode, header::CONTENT_TYPE};
+use http_body::Body;
+use http_body_util::Full;
+use tower_service::Service;
+
+use crate::models::CallbackRequest;
+use crate::webhook::WebhookVerifier;
+
+/// Processes verified callbacks.
+///
+/// Implemented for async closures returning `Result<(), E>`. An error makes the
+/// [`WebhookService`] answer `500`, so PedidosYa delivers the callback again later.
+pub trait WebhookHandler: Send + Sync + 'static {
+    fn handle(
+        &self,
+        callback: CallbackRequest,
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send;
+}
+
+impl<F, Fut, E> WebhookHandler for F
+where
+    F: Fn(CallbackRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), E>> + Send,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn handle(
+        &self,
+        callback: CallbackRequest,
+    ) -> impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send {
+        let handled = self(callback);
+        async move { handled.await.map_err(Into::into) }
+    }
+}
+
+/// `tower` service receiving PedidosYa webhook callbacks.
+///
+/// Answers `200` once the handler succeeded, `500` when it failed, and the
+/// [`crate::webhook::WebhookError::status`] of rejected callbacks. Serve it with hyper
+/// through `hyper_util::service::TowerToHyperService`, or mount it in a tower based router.
+///
+/// ```no_run
+/// use pedidosya_courier_rs::models::CallbackRequest;
+/// use pedidosya_courier_rs::webhook::{WebhookService, WebhookVerifier};
+///
+/// let service = WebhookService::new(
+///     WebhookVerifier::new("authorization-key"),
+///     |callback: CallbackRequest| async move {
+///         println!("shipping {:?} is now {:?}", callback.id, callback.data);
+///         Ok::<_, std::io::Error>(())
+///     },
+/// );
+/// ```
+pub struct WebhookService<H> {
+    verifier: Arc<WebhookVerifier>,
+    handler: Arc<H>,
+}
+
+impl<H: WebhookHandler> WebhookService<H> {
+    pub fn new(verifier: WebhookVerifier, handler: H) -> Self {
+        Self {
+            verifier: Arc::new(verifier),
+            handler: Arc::new(handler),
+        }
+    }
+
+    pub fn verifier(&self) -> &WebhookVerifier {
+        &self.verifier
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    async fn receive<B>(
+        verifier: &WebhookVerifier,
+        handler: &H,
+        request: Request<B>,
+    ) -> Response<Full<Bytes>>
+    where
+        B: Body,
+        B::Error: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let callback = match verifier.verify_request(request).await {
+            Ok(callback) => callback,
+            Err(e) => {
+                tracing::warn!(status = e.status().as_u16(), error = %e, "rejected webhook callback");
+                return response(e.status(), e.to_string());
+            }
+        };
+
+        let id = callback.id.clone();
+        match handler.handle(callback).await {
+            Ok(()) => response(StatusCode::OK, String::new()),
+            Err(e) => {
+                tracing::error!(shipping_id = id.as_deref(), error = %e, "webhook handler failed");
+                response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "could not process webhook callback".to_owned(),
+                )
+            }
+        }
+    }
+}
+
+fn response(status: StatusCode, body: String) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    if !response.body().is_end_stream() {
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            http::HeaderValue::from_static("text/plain; charset=utf-8"),
+        );
+    }
+    response
+}
+
+impl<H> Clone for WebhookService<H> {
+    fn clone(&self) -> Self {
+        Self {
+            verifier: self.verifier.clone(),
+            handler: self.handler.clone(),
+        }
+    }
+}
+
+impl<H> fmt::Debug for WebhookService<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookService")
+            .field("verifier", &self.verifier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H, B> Service<Request<B>> for WebhookService<H>
+where
+    H: WebhookHandler,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(
+            async move { Ok(Self::receive(&service.verifier, &service.handler, request).await) },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use http::header::AUTHORIZATION;
+
+    use super::*;
+
+    fn request(key: &str) -> Request<Full<Bytes>> {
+        Request::post("/webhooks")
+            .header(AUTHORIZATION, key)
+            .body(Full::new(Bytes::from_static(
+                br#"{"topic": "SHIPPING_STATUS", "id": "64000", "data": {"status": "COMPLETED"}}"#,
+            )))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_webhook_service() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let mut service = WebhookService::new(WebhookVerifier::new("key"), {
+            let received = received.clone();
+            move |callback: CallbackRequest| {
+                let received = received.clone();
+                async move {
+                    received.lock().unwrap().push(callback.id);
+                    Ok::<_, Infallible>(())
+                }
+            }
+        });
+
+        let response = service.call(request("key")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(*received.lock().unwrap(), [Some("64000".to_owned())]);
+
+        let response = service.call(request("other-key")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        let mut failing = WebhookService::new(WebhookVerifier::new("key"), |_| async {
+            Err::<(), _>("database unavailable")
+        });
+        let response = failing.call(request("key")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
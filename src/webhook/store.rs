@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::webhook::{ShippingState, Verdict, WebhookStore};
+
+/// Keeps `state` if it moves its shipping forward. Returns whether it was kept.
+fn advance(states: &mut HashMap<String, ShippingState>, state: ShippingState) -> bool {
+    if state.verdict(states.get(&state.shipping_id)) != Verdict::New {
+        return false;
+    }
+    states.insert(state.shipping_id.clone(), state);
+    true
+}
+
+/// [`WebhookStore`] kept in memory, for a single process that may forget its state on restart.
+#[derive(Debug, Default)]
+pub struct MemoryWebhookStore {
+    states: Mutex<HashMap<String, ShippingState>>,
+}
+
+impl MemoryWebhookStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn states(&self) -> MutexGuard<'_, HashMap<String, ShippingState>> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl WebhookStore for MemoryWebhookStore {
+    type Error = Infallible;
+
+    async fn last_state(&self, shipping_id: &str) -> Result<Option<ShippingState>, Infallible> {
+        Ok(self.states().get(shipping_id).cloned())
+    }
+
+    async fn record(&self, state: ShippingState) -> Result<(), Infallible> {
+        advance(&mut self.states(), state);
+        Ok(())
+    }
+}
+
+/// [`WebhookStore`] persisted to a file of JSON lines, one per recorded state.
+///
+/// Every state is appended to the file and synced to disk before
+/// [`WebhookStore::record`] returns, and is read back by [`FileWebhookStore::open`]. Use
+/// [`FileWebhookStore::compact`] to rewrite it with only the last state of each shipping.
+/// The whole state is kept in memory as well.
+///
+/// File operations are blocking, as the store does not depend on an async runtime:
+/// `record` holds the calling thread, and the lock of the store, until the file is synced.
+#[derive(Debug)]
+pub struct FileWebhookStore {
+    path: PathBuf,
+    inner: Mutex<FileState>,
+}
+
+#[derive(Debug)]
+struct FileState {
+    states: HashMap<String, ShippingState>,
+    file: File,
+}
+
+impl FileWebhookStore {
+    /// Opens the store at `path`, creating the file if needed.
+    ///
+    /// Lines that can not be parsed, such as one cut short by a crash, are skipped.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+
+        let mut states = HashMap::new();
+        for line in content.lines() {
+            match serde_json::from_str(line) {
+                Ok(state) => {
+                    advance(&mut states, state);
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipped invalid webhook store line");
+                }
+            }
+        }
+        // Terminate a line cut short, so the next state starts on its own line.
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+
+        Ok(Self {
+            path,
+            inner: Mutex::new(FileState { states, file }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Rewrites the file with only the last state of each shipping.
+    pub fn compact(&self) -> io::Result<()> {
+        let mut inner = self.inner();
+        let compacted = self.path.with_extension("compact");
+        {
+            let mut file = File::create(&compacted)?;
+            for state in inner.states.values() {
+                writeln!(file, "{}", serde_json::to_string(state)?)?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&compacted, &self.path)?;
+        inner.file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+
+    fn inner(&self) -> MutexGuard<'_, FileState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl WebhookStore for FileWebhookStore {
+    type Error = io::Error;
+
+    async fn last_state(&self, shipping_id: &str) -> io::Result<Option<ShippingState>> {
+        Ok(self.inner().states.get(shipping_id).cloned())
+    }
+
+    async fn record(&self, state: ShippingState) -> io::Result<()> {
+        let line = serde_json::to_string(&state)?;
+        let mut inner = self.inner();
+        if advance(&mut inner.states, state) {
+            writeln!(inner.file, "{line}")?;
+            inner.file.sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::CallbackShippingStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_store() {
+        use CallbackShippingStatus::*;
+
+        let dir = std::env::temp_dir().join(format!("pedidosya-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("webhooks.jsonl");
+        let state = |shipping_id: &str, status, generated: &str| ShippingState {
+            shipping_id: shipping_id.to_owned(),
+            status,
+            generated: format!("2024-01-01T12:{generated}:00Z"),
+        };
+
+        let store = FileWebhookStore::open(&path).unwrap();
+        for recorded in [
+            state("1", Confirmed, "00"),
+            state("1", PickedUp, "10"),
+            state("1", NearPickup, "05"),
+            state("2", Cancelled, "00"),
+        ] {
+            store.record(recorded).await.unwrap();
+        }
+        drop(store);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"shippingId\": \"3\"").unwrap();
+
+        let store = FileWebhookStore::open(&path).unwrap();
+        assert_eq!(
+            store.last_state("1").await.unwrap(),
+            Some(state("1", PickedUp, "10"))
+        );
+        assert_eq!(store.last_state("3").await.unwrap(), None);
+        store.record(state("2", Completed, "20")).await.unwrap();
+        store.record(state("3", Confirmed, "20")).await.unwrap();
+
+        let store = FileWebhookStore::open(&path).unwrap();
+        assert_eq!(
+            store.last_state("3").await.unwrap(),
+            Some(state("3", Confirmed, "20"))
+        );
+        store.compact().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+        store.record(state("1", Completed, "20")).await.unwrap();
+
+        let store = FileWebhookStore::open(&path).unwrap();
+        assert_eq!(
+            store.last_state("1").await.unwrap(),
+            Some(state("1", Completed, "20"))
+        );
+        assert_eq!(
+            store.last_state("2").await.unwrap(),
+            Some(state("2", Cancelled, "00"))
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+use std::{error::Error, fmt};
+
+use bytes::Buf;
+use http::{
+    HeaderMap, Method, Request, StatusCode,
+    header::{AUTHORIZATION, CONTENT_LENGTH},
+};
+use http_body::Body;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::models::CallbackRequest;
+
+/// Default limit for callback bodies. Callbacks are well under a kilobyte.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Checks that a callback comes from PedidosYa and parses it.
+///
+/// PedidosYa sends the `authorization_key` registered in [`crate::models::Urls`] as the
+/// `Authorization` header. It is compared in constant time against every configured key,
+/// so several keys can be accepted while rotating them.
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    keys: Vec<Vec<u8>>,
+    max_body_size: usize,
+}
+
+impl fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookVerifier")
+            .field("keys", &format_args!("[{} redacted]", self.keys.len()))
+            .field("max_body_size", &self.max_body_size)
+            .finish()
+    }
+}
+
+impl WebhookVerifier {
+    pub fn new(authorization_key: impl Into<String>) -> Self {
+        Self {
+            keys: vec![authorization_key.into().into_bytes()],
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Accepts `authorization_key` as well as the keys configured so far.
+    pub fn key(mut self, authorization_key: impl Into<String>) -> Self {
+        self.keys.push(authorization_key.into().into_bytes());
+        self
+    }
+
+    /// Larger bodies are rejected with [`WebhookError::PayloadTooLarge`]. Defaults to
+    /// [`DEFAULT_MAX_BODY_SIZE`].
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Checks the `Authorization` header against the configured keys.
+    pub fn authorize(&self, headers: &HeaderMap) -> Result<(), WebhookError> {
+        self.authorize_value(headers.get(AUTHORIZATION).map(|v| v.as_bytes()))
+    }
+
+    /// [`WebhookVerifier::authorize`] on the raw header value, for frameworks with their own
+    /// header types.
+    pub(crate) fn authorize_value(&self, provided: Option<&[u8]>) -> Result<(), WebhookError> {
+        let provided = provided.ok_or(WebhookError::MissingAuthorization)?;
+        let matched = self.keys.iter().fold(Choice::from(0), |matched, key| {
+            matched | key.ct_eq(provided)
+        });
+
+        if bool::from(matched) {
+            Ok(())
+        } else {
+            Err(WebhookError::InvalidAuthorization)
+        }
+    }
+
+    /// Rejects bodies announced larger than the limit by their `Content-Length` header, before
+    /// reading them.
+    pub(crate) fn check_content_length(&self, value: Option<&[u8]>) -> Result<(), WebhookError> {
+        let content_length = value
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        if content_length.is_some_and(|length| length > self.max_body_size) {
+            return Err(self.payload_too_large());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "webhook-actix")]
+    pub(crate) fn body_limit(&self) -> usize {
+        self.max_body_size
+    }
+
+    pub(crate) fn payload_too_large(&self) -> WebhookError {
+        WebhookError::PayloadTooLarge {
+            limit: self.max_body_size,
+        }
+    }
+
+    /// Parses a callback body, without checking the authorization.
+    pub fn parse(&self, body: &[u8]) -> Result<CallbackRequest, WebhookError> {
+        if body.len() > self.max_body_size {
+            return Err(self.payload_too_large());
+        }
+        serde_json::from_slice(body).map_err(WebhookError::InvalidPayload)
+    }
+
+    /// Authorizes and parses an already buffered callback.
+    pub fn verify(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<CallbackRequest, WebhookError> {
+        self.authorize(headers)?;
+        self.parse(body)
+    }
+
+    /// Authorizes `request` and reads its body, up to the size limit, into a callback.
+    ///
+    /// The body is only read once the `Authorization` header has been accepted.
+    pub async fn verify_request<B>(
+        &self,
+        request: Request<B>,
+    ) -> Result<CallbackRequest, WebhookError>
+    where
+        B: Body,
+        B::Error: Into<Box<dyn Error + Send + Sync>>,
+    {
+        if request.method() != Method::POST {
+            return Err(WebhookError::MethodNotAllowed);
+        }
+        self.authorize(request.headers())?;
+        self.check_content_length(request.headers().get(CONTENT_LENGTH).map(|v| v.as_bytes()))?;
+
+        let body = Limited::new(request.into_body(), self.max_body_size)
+            .collect()
+            .await
+            .map_err(|e| {
+                if e.is::<LengthLimitError>() {
+                    self.payload_too_large()
+                } else {
+                    WebhookError::Body(e)
+                }
+            })?
+            .aggregate();
+
+        serde_json::from_reader(body.reader()).map_err(WebhookError::InvalidPayload)
+    }
+}
+
+/// Why a callback was rejected. [`WebhookError::status`] is the status to answer with.
+#[derive(Debug)]
+pub enum WebhookError {
+    /// Callbacks are always `POST`ed.
+    MethodNotAllowed,
+    MissingAuthorization,
+    /// The `Authorization` header matches none of the configured keys.
+    InvalidAuthorization,
+    PayloadTooLarge {
+        limit: usize,
+    },
+    /// The body could not be read.
+    Body(Box<dyn Error + Send + Sync>),
+    /// The body is not a valid [`CallbackRequest`].
+    InvalidPayload(serde_json::Error),
+}
+
+impl WebhookError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            WebhookError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            WebhookError::MissingAuthorization | WebhookError::InvalidAuthorization => {
+                StatusCode::UNAUTHORIZED
+            }
+            WebhookError::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            WebhookError::Body(_) | WebhookError::InvalidPayload(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::MethodNotAllowed => write!(f, "webhook callbacks must be POST requests"),
+            WebhookError::MissingAuthorization => write!(f, "missing authorization header"),
+            WebhookError::InvalidAuthorization => write!(f, "invalid authorization header"),
+            WebhookError::PayloadTooLarge { limit } => {
+                write!(f, "webhook body exceeds {limit} bytes")
+            }
+            WebhookError::Body(e) => write!(f, "could not read webhook body: {e}"),
+            WebhookError::InvalidPayload(e) => write!(f, "invalid webhook payload: {e}"),
+        }
+    }
+}
+
+impl Error for WebhookError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WebhookError::Body(e) => Some(e.as_ref()),
+            WebhookError::InvalidPayload(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http_body_util::Full;
+
+    use crate::models::CallbackShippingStatus;
+
+    use super::*;
+
+    const CALLBACK: &str = r#"{
+        "topic": "SHIPPING_STATUS",
+        "id": "64000",
+        "referenceId": "order-1",
+        "generated": "2024-01-01T12:00:00Z",
+        "transmitted": "2024-01-01T12:00:01Z",
+        "data": {"status": "PICKED_UP"}
+    }"#;
+
+    fn request(method: Method, key: Option<&str>, body: &str) -> Request<Full<Bytes>> {
+        let mut builder = Request::builder().method(method).uri("/webhooks");
+        if let Some(key) = key {
+            builder = builder.header(AUTHORIZATION, key);
+        }
+        builder
+            .body(Full::new(Bytes::from(body.to_owned())))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_request() {
+        let verifier = WebhookVerifier::new("old-key").key("new-key");
+
+        for key in ["old-key", "new-key"] {
+            let callback = verifier
+                .verify_request(request(Method::POST, Some(key), CALLBACK))
+                .await
+                .unwrap();
+            assert_eq!(callback.id.as_deref(), Some("64000"));
+            assert_eq!(
+                callback.data.unwrap().status,
+                Some(CallbackShippingStatus::PickedUp)
+            );
+        }
+
+        let rejected = [
+            (
+                request(Method::POST, None, CALLBACK),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                request(Method::POST, Some("new-ke"), CALLBACK),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                request(Method::GET, Some("new-key"), CALLBACK),
+                StatusCode::METHOD_NOT_ALLOWED,
+            ),
+            (
+                request(Method::POST, Some("new-key"), "{\"topic\": 1}"),
+                StatusCode::BAD_REQUEST,
+            ),
+        ];
+        for (request, status) in rejected {
+            let error = verifier.verify_request(request).await.unwrap_err();
+            assert_eq!(error.status(), status, "{error}");
+        }
+
+        let error = WebhookVerifier::new("new-key")
+            .max_body_size(16)
+            .verify_request(request(Method::POST, Some("new-key"), CALLBACK))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, WebhookError::PayloadTooLarge { limit: 16 }));
+    }
+
+    #[test]
+    fn test_debug_redacts_keys() {
+        let verifier = WebhookVerifier::new("secret-key");
+
+        assert!(!format!("{verifier:?}").contains("secret-key"));
+    }
+}